@@ -1,13 +1,36 @@
 use std::error::Error;
+use std::io;
 
+use puzzler::puzzler::puzzle::Puzzle;
 use puzzler::puzzler::solver::Solver;
 
+use crate::puzzle::room_repl::RoomRepl;
+use crate::puzzle::room_report::RoomReport;
 use crate::puzzle::solution::Solution;
 
 mod puzzle;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let puzzle = Box::new(Solution::new());
-    let mut solver = Solver::new(puzzle, 2);
-    solver.run()
+    match std::env::args().nth(1).as_deref() {
+        // Interactive room search instead of solving the puzzle
+        Some("query") => {
+            let mut solution = Solution::new();
+            solution.parse_input_file()?;
+
+            RoomRepl::new(solution.get_rooms()).run(io::stdin().lock(), &mut io::stdout())
+        }
+        // Statistics over all rooms to sanity-check the input
+        Some("report") => {
+            let mut solution = Solution::new();
+            solution.parse_input_file()?;
+
+            print!("{}", RoomReport::new(solution.get_rooms()));
+            Ok(())
+        }
+        _ => {
+            let puzzle = Box::new(Solution::new());
+            let mut solver = Solver::new(puzzle, 2);
+            solver.run()
+        }
+    }
 }
@@ -1,2 +1,6 @@
 pub mod room;
+pub mod room_parser;
+pub mod room_query;
+pub mod room_repl;
+pub mod room_report;
 pub mod solution;
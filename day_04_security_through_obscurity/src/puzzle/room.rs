@@ -1,5 +1,16 @@
 use std::collections::HashMap;
 
+// Why checksum does not match the room name
+#[derive(Debug, PartialEq)]
+pub enum DecoyReason {
+    // Checksum letter does not occur in the name at all
+    MissingLetter(char),
+    // Checksum letter is listed more than once
+    RepeatedLetter(char),
+    // Letters are present but not the most common ones, holds the expected checksum
+    WrongOrder(String),
+}
+
 #[derive(Debug)]
 pub struct Room {
     name: String,
@@ -20,7 +31,40 @@ impl Room {
         self.sector_id
     }
 
+    pub fn get_checksum(&self) -> &str {
+        &self.checksum
+    }
+
     pub fn is_real(&self) -> bool {
+        self.decoy_reason().is_none()
+    }
+
+    pub fn decoy_reason(&self) -> Option<DecoyReason> {
+        let counts = self.letter_counts();
+
+        for (index, c) in self.checksum.chars().enumerate() {
+            if !counts.contains_key(&c) {
+                return Some(DecoyReason::MissingLetter(c));
+            }
+
+            if self.checksum.chars().take(index).any(|other| other == c) {
+                return Some(DecoyReason::RepeatedLetter(c));
+            }
+        }
+
+        // Sort items and crate full checksum
+        let sorted = Self::sort_counts(&counts);
+        let full_checksum = sorted.iter().map(|(c, _size)| *c).collect::<String>();
+
+        if full_checksum.starts_with(&self.checksum) {
+            None
+        } else {
+            let expected = full_checksum.chars().take(self.checksum.len()).collect();
+            Some(DecoyReason::WrongOrder(expected))
+        }
+    }
+
+    pub fn letter_counts(&self) -> HashMap<char, usize> {
         // Holds counts for all characters
         let mut counts: HashMap<char, usize> = HashMap::new();
 
@@ -34,14 +78,10 @@ impl Room {
             }
         }
 
-        // Sort items and crate full checksum
-        let sorted = Self::sort_counts(&counts);
-        let full_checksum = sorted.iter().map(|(c, _size)| *c).collect::<String>();
-
-        full_checksum.starts_with(&self.checksum)
+        counts
     }
 
-    fn sort_counts(counts: &HashMap<char, usize>) -> Vec<(char, usize)> {
+    pub fn sort_counts(counts: &HashMap<char, usize>) -> Vec<(char, usize)> {
         let mut sorted = counts
             .iter()
             .map(|(c, size)| (*c, *size))
@@ -104,6 +144,26 @@ mod tests {
         assert!(!Room::new("totally-real-room", 200, "decoy").is_real());
     }
 
+    #[test]
+    fn test_decoy_reason() {
+        assert_eq!(
+            Room::new("totally-real-room", 200, "decoy").decoy_reason(),
+            Some(DecoyReason::MissingLetter('d'))
+        );
+        assert_eq!(
+            Room::new("aaaaa-bbb-z-y-x", 123, "abaxy").decoy_reason(),
+            Some(DecoyReason::RepeatedLetter('a'))
+        );
+        assert_eq!(
+            Room::new("aaaaa-bbb-z-y-x", 123, "baxyz").decoy_reason(),
+            Some(DecoyReason::WrongOrder(String::from("abxyz")))
+        );
+        assert_eq!(
+            Room::new("aaaaa-bbb-z-y-x", 123, "abxyz").decoy_reason(),
+            None
+        );
+    }
+
     #[test]
     fn test_decode_name() {
         assert_eq!(
@@ -0,0 +1,445 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::puzzle::room::Room;
+
+pub const CHECKSUM_LENGTH: usize = 5;
+
+// Line numbers are 1-based, offsets are byte offsets within the line
+#[derive(Debug, PartialEq)]
+pub enum RoomParseError {
+    EmptyNameSegment {
+        line: usize,
+        offset: usize,
+    },
+    InvalidNameChar {
+        line: usize,
+        offset: usize,
+        found: char,
+    },
+    MissingSectorId {
+        line: usize,
+        offset: usize,
+    },
+    SectorIdOverflow {
+        line: usize,
+        offset: usize,
+    },
+    Expected {
+        line: usize,
+        offset: usize,
+        expected: char,
+        found: Option<char>,
+    },
+    InvalidChecksumChar {
+        line: usize,
+        offset: usize,
+        found: char,
+    },
+    InvalidChecksumLength {
+        line: usize,
+        offset: usize,
+        length: usize,
+    },
+    TrailingCharacters {
+        line: usize,
+        offset: usize,
+    },
+}
+
+impl fmt::Display for RoomParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyNameSegment { line, offset } => {
+                write!(f, "line {line}, offset {offset}: empty name segment")
+            }
+            Self::InvalidNameChar {
+                line,
+                offset,
+                found,
+            } => write!(
+                f,
+                "line {line}, offset {offset}: invalid character '{found}' in name, expected 'a'-'z' or '-'"
+            ),
+            Self::MissingSectorId { line, offset } => {
+                write!(f, "line {line}, offset {offset}: missing sector id")
+            }
+            Self::SectorIdOverflow { line, offset } => {
+                write!(
+                    f,
+                    "line {line}, offset {offset}: sector id does not fit into usize"
+                )
+            }
+            Self::Expected {
+                line,
+                offset,
+                expected,
+                found: Some(found),
+            } => write!(
+                f,
+                "line {line}, offset {offset}: expected '{expected}', found '{found}'"
+            ),
+            Self::Expected {
+                line,
+                offset,
+                expected,
+                found: None,
+            } => write!(
+                f,
+                "line {line}, offset {offset}: expected '{expected}', found end of line"
+            ),
+            Self::InvalidChecksumChar {
+                line,
+                offset,
+                found,
+            } => write!(
+                f,
+                "line {line}, offset {offset}: invalid character '{found}' in checksum, expected 'a'-'z'"
+            ),
+            Self::InvalidChecksumLength {
+                line,
+                offset,
+                length,
+            } => write!(
+                f,
+                "line {line}, offset {offset}: checksum has {length} characters, expected {CHECKSUM_LENGTH}"
+            ),
+            Self::TrailingCharacters { line, offset } => {
+                write!(
+                    f,
+                    "line {line}, offset {offset}: unexpected characters after checksum"
+                )
+            }
+        }
+    }
+}
+
+impl Error for RoomParseError {}
+
+// Grammar: name ("-" name)* "-" sector "[" checksum "]"
+//   name     = [a-z]+
+//   sector   = [0-9]+
+//   checksum = [a-z]{5}
+pub struct RoomParser<'a> {
+    line: usize,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> RoomParser<'a> {
+    pub fn parse_lines(lines: &[String]) -> Result<Vec<Room>, RoomParseError> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| RoomParser::parse_line(index + 1, line))
+            .collect()
+    }
+
+    pub fn parse_line(line: usize, text: &'a str) -> Result<Room, RoomParseError> {
+        let mut parser = Self {
+            line,
+            text,
+            offset: 0,
+        };
+
+        let name = parser.parse_name()?;
+        let sector_id = parser.parse_sector_id()?;
+        parser.expect('[')?;
+        let checksum = parser.parse_checksum()?;
+        parser.expect(']')?;
+
+        if parser.offset != text.len() {
+            return Err(RoomParseError::TrailingCharacters {
+                line,
+                offset: parser.offset,
+            });
+        }
+
+        Ok(Room::new(name, sector_id, checksum))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), RoomParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.offset += c.len_utf8();
+                Ok(())
+            }
+            found => Err(RoomParseError::Expected {
+                line: self.line,
+                offset: self.offset,
+                expected,
+                found,
+            }),
+        }
+    }
+
+    // Consumes name including the dash in front of the sector id
+    fn parse_name(&mut self) -> Result<&'a str, RoomParseError> {
+        let start = self.offset;
+        let mut segment_start = self.offset;
+
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_lowercase() => self.offset += 1,
+                Some('-') | None if self.offset == segment_start => {
+                    return Err(RoomParseError::EmptyNameSegment {
+                        line: self.line,
+                        offset: self.offset,
+                    });
+                }
+                Some('-') => {
+                    let end = self.offset;
+                    self.offset += 1;
+
+                    // Digit after dash starts the sector id, name is complete
+                    if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        return Ok(&self.text[start..end]);
+                    }
+
+                    segment_start = self.offset;
+                }
+                None => {
+                    return Err(RoomParseError::MissingSectorId {
+                        line: self.line,
+                        offset: self.offset,
+                    });
+                }
+                Some(found) => {
+                    return Err(RoomParseError::InvalidNameChar {
+                        line: self.line,
+                        offset: self.offset,
+                        found,
+                    });
+                }
+            }
+        }
+    }
+
+    fn parse_sector_id(&mut self) -> Result<usize, RoomParseError> {
+        let start = self.offset;
+
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.offset += 1;
+        }
+
+        self.text[start..self.offset]
+            .parse::<usize>()
+            .map_err(|_err| RoomParseError::SectorIdOverflow {
+                line: self.line,
+                offset: start,
+            })
+    }
+
+    fn parse_checksum(&mut self) -> Result<&'a str, RoomParseError> {
+        let start = self.offset;
+
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_lowercase() => self.offset += 1,
+                Some(']') | None => break,
+                Some(found) => {
+                    return Err(RoomParseError::InvalidChecksumChar {
+                        line: self.line,
+                        offset: self.offset,
+                        found,
+                    });
+                }
+            }
+        }
+
+        let length = self.offset - start;
+        if length != CHECKSUM_LENGTH {
+            return Err(RoomParseError::InvalidChecksumLength {
+                line: self.line,
+                offset: start,
+                length,
+            });
+        }
+
+        Ok(&self.text[start..self.offset])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Room, RoomParseError> {
+        RoomParser::parse_line(1, text)
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let room = parse("aaaaa-bbb-z-y-x-123[abxyz]").unwrap();
+
+        assert_eq!(room.get_sector_id(), 123);
+        assert_eq!(room.get_checksum(), "abxyz");
+        assert!(room.is_real());
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let lines = vec![
+            String::from("qzmt-zixmtkozy-ivhz-343[zimth]"),
+            String::from("not-a-real-room-404[oarel]"),
+        ];
+
+        let rooms = RoomParser::parse_lines(&lines).unwrap();
+        assert_eq!(rooms.len(), 2);
+        assert_eq!(rooms[1].get_sector_id(), 404);
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line() {
+        let lines = vec![
+            String::from("qzmt-zixmtkozy-ivhz-343[zimth]"),
+            String::from("not-a-Real-room-404[oarel]"),
+        ];
+
+        assert_eq!(
+            RoomParser::parse_lines(&lines).unwrap_err(),
+            RoomParseError::InvalidNameChar {
+                line: 2,
+                offset: 6,
+                found: 'R'
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty_name_segment() {
+        assert_eq!(
+            parse("-abc-123[abcde]").unwrap_err(),
+            RoomParseError::EmptyNameSegment { line: 1, offset: 0 }
+        );
+        assert_eq!(
+            parse("abc--def-123[abcde]").unwrap_err(),
+            RoomParseError::EmptyNameSegment { line: 1, offset: 4 }
+        );
+        assert_eq!(
+            parse("123[abcde]").unwrap_err(),
+            RoomParseError::InvalidNameChar {
+                line: 1,
+                offset: 0,
+                found: '1'
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_name_char() {
+        assert_eq!(
+            parse("abc-d4f-123[abcde]").unwrap_err(),
+            RoomParseError::InvalidNameChar {
+                line: 1,
+                offset: 5,
+                found: '4'
+            }
+        );
+        assert_eq!(
+            parse("abč-123[abcde]").unwrap_err(),
+            RoomParseError::InvalidNameChar {
+                line: 1,
+                offset: 2,
+                found: 'č'
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_sector_id() {
+        assert_eq!(
+            parse("abc-def").unwrap_err(),
+            RoomParseError::MissingSectorId { line: 1, offset: 7 }
+        );
+        assert_eq!(
+            parse("abc-def-[abcde]").unwrap_err(),
+            RoomParseError::InvalidNameChar {
+                line: 1,
+                offset: 8,
+                found: '['
+            }
+        );
+    }
+
+    #[test]
+    fn test_sector_id_overflow() {
+        assert_eq!(
+            parse("abc-99999999999999999999999[abcde]").unwrap_err(),
+            RoomParseError::SectorIdOverflow { line: 1, offset: 4 }
+        );
+    }
+
+    #[test]
+    fn test_expected() {
+        assert_eq!(
+            parse("abc-123(abcde)").unwrap_err(),
+            RoomParseError::Expected {
+                line: 1,
+                offset: 7,
+                expected: '[',
+                found: Some('(')
+            }
+        );
+        assert_eq!(
+            parse("abc-123[abcde").unwrap_err(),
+            RoomParseError::Expected {
+                line: 1,
+                offset: 13,
+                expected: ']',
+                found: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_checksum() {
+        assert_eq!(
+            parse("abc-123[abCde]").unwrap_err(),
+            RoomParseError::InvalidChecksumChar {
+                line: 1,
+                offset: 10,
+                found: 'C'
+            }
+        );
+        assert_eq!(
+            parse("abc-123[abcd]").unwrap_err(),
+            RoomParseError::InvalidChecksumLength {
+                line: 1,
+                offset: 8,
+                length: 4
+            }
+        );
+        assert_eq!(
+            parse("abc-123[abcdef]").unwrap_err(),
+            RoomParseError::InvalidChecksumLength {
+                line: 1,
+                offset: 8,
+                length: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_trailing_characters() {
+        assert_eq!(
+            parse("abc-123[abcde] ").unwrap_err(),
+            RoomParseError::TrailingCharacters {
+                line: 1,
+                offset: 14
+            }
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            parse("abc-123[abcd]").unwrap_err().to_string(),
+            "line 1, offset 8: checksum has 4 characters, expected 5"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::ops::RangeInclusive;
+
+use regex::Regex;
+
+use crate::puzzle::room::Room;
+
+#[derive(Debug, Default, Clone)]
+pub struct RoomQuery {
+    real: Option<bool>,
+    name: Option<Regex>,
+    sectors: Option<RangeInclusive<usize>>,
+    checksum: Option<String>,
+}
+
+impl RoomQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Keep only real rooms (true) or only decoys (false)
+    pub fn real(mut self, real: bool) -> Self {
+        self.real = Some(real);
+        self
+    }
+
+    // Regex is matched against the decoded room name
+    pub fn name(mut self, pattern: &str) -> Result<Self, Box<dyn Error>> {
+        let regex = Regex::new(pattern).map_err(|err| -> Box<dyn Error> {
+            format!("Invalid regex '{pattern}' [{err}]").into()
+        })?;
+
+        self.name = Some(regex);
+        Ok(self)
+    }
+
+    pub fn sectors(mut self, sectors: RangeInclusive<usize>) -> Self {
+        self.sectors = Some(sectors);
+        self
+    }
+
+    // Checksum has to contain all of the letters, order does not matter
+    pub fn checksum(mut self, letters: &str) -> Self {
+        self.checksum = Some(letters.to_string());
+        self
+    }
+
+    pub fn matches(&self, room: &Room) -> bool {
+        // Decoding is the most expensive check, keep it last
+        self.sectors
+            .as_ref()
+            .is_none_or(|sectors| sectors.contains(&room.get_sector_id()))
+            && self
+                .checksum
+                .as_ref()
+                .is_none_or(|letters| letters.chars().all(|c| room.get_checksum().contains(c)))
+            && self.real.is_none_or(|real| room.is_real() == real)
+            && self
+                .name
+                .as_ref()
+                .is_none_or(|name| name.is_match(&room.decode_name()))
+    }
+
+    // Returns matching rooms ordered by sector id
+    pub fn run<'a>(&self, rooms: &'a [Room]) -> Vec<&'a Room> {
+        let mut found = rooms
+            .iter()
+            .filter(|room| self.matches(room))
+            .collect::<Vec<_>>();
+
+        found.sort_by_key(|room| room.get_sector_id());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_rooms() -> Vec<Room> {
+        vec![
+            Room::new("ghkmaihex-hucxvm-lmhktzx", 267, "hmxka"),
+            Room::new("aaaaa-bbb-z-y-x", 123, "abxyz"),
+            Room::new("totally-real-room", 200, "decoy"),
+            Room::new("qzmt-zixmtkozy-ivhz", 343, "zimth"),
+            Room::new("not-a-real-room", 404, "oarel"),
+        ]
+    }
+
+    fn sectors(rooms: Vec<&Room>) -> Vec<usize> {
+        rooms.iter().map(|room| room.get_sector_id()).collect()
+    }
+
+    #[test]
+    fn test_run_sorted_by_sector() {
+        let rooms = get_rooms();
+
+        assert_eq!(
+            sectors(RoomQuery::new().run(&rooms)),
+            vec![123, 200, 267, 343, 404]
+        );
+    }
+
+    #[test]
+    fn test_real() {
+        let rooms = get_rooms();
+
+        assert_eq!(
+            sectors(RoomQuery::new().real(true).run(&rooms)),
+            vec![123, 267, 343, 404]
+        );
+        assert_eq!(sectors(RoomQuery::new().real(false).run(&rooms)), vec![200]);
+    }
+
+    #[test]
+    fn test_name() {
+        let rooms = get_rooms();
+        let query = RoomQuery::new().name(r"^northpole\b").unwrap();
+
+        assert_eq!(sectors(query.run(&rooms)), vec![267]);
+    }
+
+    #[test]
+    fn test_name_invalid_regex() {
+        assert!(RoomQuery::new().name("(northpole").is_err());
+    }
+
+    #[test]
+    fn test_sectors() {
+        let rooms = get_rooms();
+
+        assert_eq!(
+            sectors(RoomQuery::new().sectors(200..=343).run(&rooms)),
+            vec![200, 267, 343]
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        let rooms = get_rooms();
+
+        assert_eq!(
+            sectors(RoomQuery::new().checksum("xa").run(&rooms)),
+            vec![123, 267]
+        );
+    }
+
+    #[test]
+    fn test_combined() {
+        let rooms = get_rooms();
+        let query = RoomQuery::new()
+            .real(true)
+            .sectors(100..=300)
+            .checksum("x")
+            .name("object")
+            .unwrap();
+
+        assert_eq!(sectors(query.run(&rooms)), vec![267]);
+    }
+}
@@ -0,0 +1,180 @@
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+use crate::puzzle::room::Room;
+use crate::puzzle::room_query::RoomQuery;
+
+const HELP: &str = "\
+Commands:
+  real                 only real rooms
+  decoy                only decoy rooms
+  name <regex>         decoded name matches regex
+  sector <min> <max>   sector id in range (inclusive)
+  checksum <letters>   checksum contains all letters
+  list                 print matching rooms
+  clear                drop all filters
+  help                 print this help
+  quit                 exit";
+
+pub struct RoomRepl<'a> {
+    rooms: &'a [Room],
+    query: RoomQuery,
+}
+
+impl<'a> RoomRepl<'a> {
+    pub fn new(rooms: &'a [Room]) -> Self {
+        Self {
+            rooms,
+            query: RoomQuery::new(),
+        }
+    }
+
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        input: R,
+        output: &mut W,
+    ) -> Result<(), Box<dyn Error>> {
+        writeln!(
+            output,
+            "{} rooms loaded, type 'help' for commands",
+            self.rooms.len()
+        )?;
+
+        for line in input.lines() {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line == "quit" {
+                break;
+            }
+
+            // Bad command should not end the session
+            if let Err(err) = self.execute(line, output) {
+                writeln!(output, "error: {err}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute<W: Write>(&mut self, line: &str, output: &mut W) -> Result<(), Box<dyn Error>> {
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args = args.trim();
+
+        // Builder consumes the query, work on a copy so a failed command keeps the filters
+        let query = self.query.clone();
+
+        self.query = match command {
+            "real" => query.real(true),
+            "decoy" => query.real(false),
+            "name" => query.name(args)?,
+            "sector" => {
+                let bounds = args
+                    .split_whitespace()
+                    .map(|bound| {
+                        bound.parse::<usize>().map_err(|_err| -> Box<dyn Error> {
+                            format!("Failed to convert '{bound}' to usize").into()
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if bounds.len() != 2 {
+                    return Err(
+                        format!("Exactly 2 bounds are expected, found {}", bounds.len()).into(),
+                    );
+                }
+
+                if bounds[0] > bounds[1] {
+                    return Err(format!(
+                        "Minimum {} is greater than maximum {}",
+                        bounds[0], bounds[1]
+                    )
+                    .into());
+                }
+
+                query.sectors(bounds[0]..=bounds[1])
+            }
+            "checksum" => query.checksum(args),
+            "clear" => RoomQuery::new(),
+            "list" => {
+                let found = query.run(self.rooms);
+
+                for room in &found {
+                    writeln!(
+                        output,
+                        "{:>4} [{}] {}",
+                        room.get_sector_id(),
+                        room.get_checksum(),
+                        room.decode_name()
+                    )?;
+                }
+                writeln!(output, "{} rooms found", found.len())?;
+
+                query
+            }
+            "help" => {
+                writeln!(output, "{HELP}")?;
+                query
+            }
+            _ => return Err(format!("Unknown command '{command}'").into()),
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_session(commands: &str) -> String {
+        let rooms = vec![
+            Room::new("ghkmaihex-hucxvm-lmhktzx", 267, "hmxka"),
+            Room::new("totally-real-room", 200, "decoy"),
+            Room::new("qzmt-zixmtkozy-ivhz", 343, "zimth"),
+        ];
+        let mut output = Vec::new();
+
+        RoomRepl::new(&rooms)
+            .run(commands.as_bytes(), &mut output)
+            .unwrap_or_else(|err| panic!("Session failed [{err}]"));
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(
+            run_session("real\nsector 250 400\nlist\n"),
+            "3 rooms loaded, type 'help' for commands\n\
+             \x20267 [hmxka] northpole object storage\n\
+             \x20343 [zimth] very encrypted name\n\
+             2 rooms found\n"
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        assert!(run_session("decoy\nclear\nlist\n").ends_with("3 rooms found\n"));
+    }
+
+    #[test]
+    fn test_errors_keep_query() {
+        let output = run_session("decoy\nsector 1\nsector 400 250\nname (\nfoo\nlist\n");
+
+        assert!(output.contains("error: Exactly 2 bounds are expected, found 1"));
+        assert!(output.contains("error: Minimum 400 is greater than maximum 250"));
+        assert!(output.contains("error: Invalid regex '('"));
+        assert!(output.contains("error: Unknown command 'foo'"));
+        assert!(output.ends_with("1 rooms found\n"));
+    }
+
+    #[test]
+    fn test_quit() {
+        assert!(!run_session("quit\nlist\n").contains("rooms found"));
+    }
+}
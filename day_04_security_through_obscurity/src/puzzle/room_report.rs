@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::puzzle::room::{DecoyReason, Room};
+
+const LETTERS: usize = 26;
+const SECTOR_BUCKET: usize = 100;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DecoyCounts {
+    pub missing_letter: usize,
+    pub repeated_letter: usize,
+    pub wrong_order: usize,
+}
+
+impl DecoyCounts {
+    pub fn total(&self) -> usize {
+        self.missing_letter + self.repeated_letter + self.wrong_order
+    }
+}
+
+#[derive(Debug)]
+pub struct RoomReport {
+    pub rooms: usize,
+    pub decoys: DecoyCounts,
+    // Ordered by count, then alphabetically (same as room checksum)
+    pub encrypted_frequency: Vec<(char, usize)>,
+    pub decrypted_frequency: Vec<(char, usize)>,
+    // Bucket start -> number of rooms in [start, start + SECTOR_BUCKET)
+    pub sector_buckets: BTreeMap<usize, usize>,
+    // Rotation (sector id mod 26) -> sector ids sharing it, only rotations with 2+ ids
+    pub rotation_collisions: BTreeMap<usize, Vec<usize>>,
+}
+
+impl RoomReport {
+    pub fn new(rooms: &[Room]) -> Self {
+        let mut decoys = DecoyCounts::default();
+        let mut encrypted: HashMap<char, usize> = HashMap::new();
+        let mut decrypted: HashMap<char, usize> = HashMap::new();
+        let mut sector_buckets = BTreeMap::new();
+        let mut rotations: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
+        for room in rooms {
+            match room.decoy_reason() {
+                Some(DecoyReason::MissingLetter(_)) => decoys.missing_letter += 1,
+                Some(DecoyReason::RepeatedLetter(_)) => decoys.repeated_letter += 1,
+                Some(DecoyReason::WrongOrder(_)) => decoys.wrong_order += 1,
+                None => {}
+            }
+
+            for (c, count) in room.letter_counts() {
+                *encrypted.entry(c).or_insert(0) += count;
+            }
+
+            for c in room.decode_name().chars().filter(|c| *c != ' ') {
+                *decrypted.entry(c).or_insert(0) += 1;
+            }
+
+            let sector_id = room.get_sector_id();
+            *sector_buckets
+                .entry(sector_id / SECTOR_BUCKET * SECTOR_BUCKET)
+                .or_insert(0) += 1;
+
+            let ids = rotations.entry(sector_id % LETTERS).or_default();
+            if !ids.contains(&sector_id) {
+                ids.push(sector_id);
+            }
+        }
+
+        rotations.retain(|_rotation, ids| ids.len() > 1);
+        rotations.values_mut().for_each(|ids| ids.sort());
+
+        Self {
+            rooms: rooms.len(),
+            decoys,
+            encrypted_frequency: Room::sort_counts(&encrypted),
+            decrypted_frequency: Room::sort_counts(&decrypted),
+            sector_buckets,
+            rotation_collisions: rotations,
+        }
+    }
+
+    fn write_frequency(f: &mut fmt::Formatter<'_>, frequency: &[(char, usize)]) -> fmt::Result {
+        let items = frequency
+            .iter()
+            .map(|(c, count)| format!("{c}={count}"))
+            .collect::<Vec<_>>();
+
+        writeln!(f, "  {}", items.join(" "))
+    }
+}
+
+impl fmt::Display for RoomReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Rooms: {} (real {}, decoy {})",
+            self.rooms,
+            self.rooms - self.decoys.total(),
+            self.decoys.total()
+        )?;
+        writeln!(f, "Decoys:")?;
+        writeln!(
+            f,
+            "  checksum letter missing in name: {}",
+            self.decoys.missing_letter
+        )?;
+        writeln!(
+            f,
+            "  checksum letter repeated: {}",
+            self.decoys.repeated_letter
+        )?;
+        writeln!(
+            f,
+            "  checksum letters in wrong order: {}",
+            self.decoys.wrong_order
+        )?;
+
+        writeln!(f, "Letter frequency (encrypted):")?;
+        Self::write_frequency(f, &self.encrypted_frequency)?;
+        writeln!(f, "Letter frequency (decrypted):")?;
+        Self::write_frequency(f, &self.decrypted_frequency)?;
+
+        writeln!(f, "Sector ids:")?;
+        for (start, count) in &self.sector_buckets {
+            writeln!(f, "  {:>4}-{:<4} {count}", start, start + SECTOR_BUCKET - 1)?;
+        }
+
+        writeln!(f, "Rotation collisions (sector id mod {LETTERS}):")?;
+        for (rotation, ids) in &self.rotation_collisions {
+            let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+            writeln!(f, "  {rotation:>2}: {}", ids.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_report() -> RoomReport {
+        RoomReport::new(&[
+            Room::new("aaaaa-bbb-z-y-x", 123, "abxyz"),
+            Room::new("a-b-c-d-e-f-g-h", 987, "abcde"),
+            Room::new("not-a-real-room", 404, "oarel"),
+            Room::new("totally-real-room", 200, "decoy"),
+            Room::new("qzmt-zixmtkozy-ivhz", 343, "zimth"),
+            Room::new("abc", 149, "bca"),
+            Room::new("abc-d", 250, "aabcd"),
+        ])
+    }
+
+    #[test]
+    fn test_decoys() {
+        let report = get_report();
+
+        assert_eq!(report.rooms, 7);
+        assert_eq!(
+            report.decoys,
+            DecoyCounts {
+                missing_letter: 1,
+                repeated_letter: 1,
+                wrong_order: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_frequency() {
+        let report = RoomReport::new(&[Room::new("aab-c", 1, "abc"), Room::new("a", 2, "a")]);
+
+        assert_eq!(
+            report.encrypted_frequency,
+            vec![('a', 3), ('b', 1), ('c', 1)]
+        );
+        assert_eq!(
+            report.decrypted_frequency,
+            vec![('b', 2), ('c', 2), ('d', 1)]
+        );
+    }
+
+    #[test]
+    fn test_sector_buckets() {
+        let report = get_report();
+
+        assert_eq!(
+            report.sector_buckets,
+            BTreeMap::from([(100, 2), (200, 2), (300, 1), (400, 1), (900, 1)])
+        );
+    }
+
+    #[test]
+    fn test_rotation_collisions() {
+        let report = get_report();
+
+        // 123 % 26 == 19, 149 % 26 == 19
+        assert_eq!(
+            report.rotation_collisions,
+            BTreeMap::from([(19, vec![123, 149])])
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let report = get_report().to_string();
+
+        assert!(report.starts_with("Rooms: 7 (real 4, decoy 3)\n"));
+        assert!(report.contains("   100-199  2\n"));
+        assert!(report.ends_with("  19: 123, 149\n"));
+    }
+}
@@ -2,10 +2,11 @@ use std::error::Error;
 use std::path::PathBuf;
 
 use puzzler::env::project;
-use puzzler::parsers::parser::Parser;
 use puzzler::puzzler::puzzle::Puzzle;
 
 use crate::puzzle::room::Room;
+use crate::puzzle::room_parser::RoomParser;
+use crate::puzzle::room_query::RoomQuery;
 
 pub struct Solution {
     rooms: Vec<Room>,
@@ -24,21 +25,7 @@ impl Puzzle for Solution {
     }
 
     fn parse_content(&mut self, lines: Vec<String>) -> Result<(), Box<dyn Error>> {
-        self.rooms = Parser::parse_lines_with_regex(lines, r"(.*)-(\d+)\[(\w+)]$", |parts| {
-            if parts.len() != 3 {
-                return Err(format!("Exactly 3 parts are expected, found {}", parts.len()).into());
-            }
-
-            let name = parts[0].as_str();
-            let id = parts[1]
-                .parse::<usize>()
-                .map_err(|_err| -> Box<dyn Error> {
-                    format!("Failed to convert '{}' to usize", parts[1]).into()
-                })?;
-            let checksum = parts[2].as_str();
-
-            Ok(Room::new(name, id, checksum))
-        })?;
+        self.rooms = RoomParser::parse_lines(&lines)?;
         Ok(())
     }
 
@@ -54,11 +41,12 @@ impl Puzzle for Solution {
     }
 
     fn solve_part2(&mut self) -> Result<String, Box<dyn Error>> {
-        let storage_name = String::from("northpole object storage");
-        let room = self
-            .rooms
-            .iter()
-            .find(|room| room.decode_name() == storage_name)
+        let storage_name = "northpole object storage";
+        let query = RoomQuery::new().name(&format!("^{}$", regex::escape(storage_name)))?;
+        let room = query
+            .run(&self.rooms)
+            .into_iter()
+            .next()
             .ok_or(format!("Failed to find '{storage_name}'"))?;
 
         Ok(room.get_sector_id().to_string())
@@ -69,6 +57,10 @@ impl Solution {
     pub fn new() -> Self {
         Self { rooms: Vec::new() }
     }
+
+    pub fn get_rooms(&self) -> &[Room] {
+        &self.rooms
+    }
 }
 
 #[cfg(test)]
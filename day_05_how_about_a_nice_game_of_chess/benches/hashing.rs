@@ -0,0 +1,84 @@
+// Benchmarks of the hashing hot loop. Throughput is reported in elements per second, for the
+// digest and crack groups these are hashes per second.
+//
+// Compare two versions with criterion baselines:
+//   cargo bench -p day_05_how_about_a_nice_game_of_chess -- --save-baseline before
+//   (apply changes)
+//   cargo bench -p day_05_how_about_a_nice_game_of_chess -- --baseline before
+//
+// Multi-lane MD5 is measured with `--features multi_lane`, without it `md5_lanes` hashes the
+// lanes one by one.
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
+use day_05_how_about_a_nice_game_of_chess::puzzle::hacker::Hacker;
+use day_05_how_about_a_nice_game_of_chess::puzzle::hash_algorithm::{HashAlgorithm, LANES, Md5};
+use day_05_how_about_a_nice_game_of_chess::puzzle::miner::{Miner, NUMBER_DIGITS};
+use day_05_how_about_a_nice_game_of_chess::puzzle::password_rule::PasswordRule;
+
+// Short door id with a known password, see puzzle description
+const DOOR_ID: &str = "abc";
+
+fn bench_number_to_u8(c: &mut Criterion) {
+    let mut group = c.benchmark_group("number_to_u8");
+    group.throughput(Throughput::Elements(1));
+
+    for (name, number) in [
+        ("1_digit", 7),
+        ("7_digits", 3_231_929),
+        ("20_digits", u64::MAX),
+    ] {
+        group.bench_function(name, |b| {
+            let mut array = [0_u8; NUMBER_DIGITS];
+            b.iter(|| Miner::<Md5>::number_to_u8(black_box(number), &mut array))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_digest(c: &mut Criterion) {
+    let mut group = c.benchmark_group("digest");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("md5", |b| b.iter(|| Md5.hash(black_box(b"abc3231929"))));
+
+    group.throughput(Throughput::Elements(LANES as u64));
+    group.bench_function("md5_lanes", |b| {
+        let data = [b"abc3231929".as_slice(); LANES];
+        b.iter(|| Md5.hash_lanes(black_box(&data)))
+    });
+
+    group.finish();
+}
+
+fn bench_crack_password(c: &mut Criterion) {
+    // Indices up to the last hit, parallel blocks may hash a few more beyond it
+    let last = Miner::new(DOOR_ID, PasswordRule::simple().condition())
+        .iter()
+        .nth(PasswordRule::simple().length - 1)
+        .map(|(index, _digest)| index)
+        .expect("Password of the benchmark door id not found");
+
+    let mut group = c.benchmark_group("crack");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(last + 1));
+
+    group.bench_function("crack_password", |b| {
+        b.iter_batched(
+            || Hacker::new(DOOR_ID),
+            |hacker| hacker.crack_password().unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_number_to_u8,
+    bench_digest,
+    bench_crack_password
+);
+criterion_main!(benches);
@@ -1,13 +1,36 @@
 use std::error::Error;
+use std::path::PathBuf;
 
+use day_05_how_about_a_nice_game_of_chess::puzzle::solution::Solution;
 use puzzler::puzzler::solver::Solver;
 
-use crate::puzzle::solution::Solution;
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut solution = Solution::new();
+    let mut args = std::env::args().skip(1);
 
-mod puzzle;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Shows the password being decrypted
+            "--live" => solution = solution.with_live_display(true),
+            // Resume killed runs from the checkpoint file
+            "--checkpoint" => {
+                let path = args.next().ok_or("Missing path after '--checkpoint'")?;
+                solution = solution.with_checkpoint(&PathBuf::from(path));
+            }
+            // Answer both parts from a stored hit index
+            "--index" => {
+                let path = args.next().ok_or("Missing path after '--index'")?;
+                solution = solution.with_index(&PathBuf::from(path));
+            }
+            arg if arg.starts_with("--") => {
+                return Err(format!("Unknown argument '{arg}'").into());
+            }
+            // Door id overrides the input file
+            door_id => solution = solution.with_door_id(door_id)?,
+        }
+    }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let puzzle = Box::new(Solution::new());
+    let puzzle = Box::new(solution);
     let mut solver = Solver::new(puzzle, 2);
     solver.run()
 }
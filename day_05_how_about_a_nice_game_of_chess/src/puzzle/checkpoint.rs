@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::puzzle::password_rule::PasswordRule;
+
+// Progress of one cracking run, `next_index` is the first index not searched yet
+#[derive(Debug, PartialEq)]
+pub struct Checkpoint {
+    pub door_id: String,
+    // HashAlgorithm::id of the algorithm that searched the indexes
+    pub algorithm: String,
+    pub rule: PasswordRule,
+    pub next_index: u64,
+    pub password: String,
+}
+
+impl Checkpoint {
+    // Returns None if there is no checkpoint yet
+    pub fn load(path: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        Self::parse(&content).map(Some)
+    }
+
+    fn parse(content: &str) -> Result<Self, Box<dyn Error>> {
+        let mut lines = content.lines();
+        let mut field = |name: &str| -> Result<&str, Box<dyn Error>> {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|line| line.strip_prefix('='))
+                .ok_or_else(|| format!("Checkpoint is missing '{name}'").into())
+        };
+
+        let door_id = field("door_id")?.to_string();
+        let algorithm = field("algorithm")?.to_string();
+        let rule = field("rule")?.parse::<PasswordRule>()?;
+        let next_index = field("next_index")?;
+        let next_index = next_index
+            .parse::<u64>()
+            .map_err(|_err| -> Box<dyn Error> {
+                format!("Failed to convert '{next_index}' to u64").into()
+            })?;
+        let password = field("password")?.to_string();
+
+        Ok(Self {
+            door_id,
+            algorithm,
+            rule,
+            next_index,
+            password,
+        })
+    }
+
+    // Content is written to a temporary file first and then renamed over the old one. Rename
+    // is atomic, so the file on disk is always either the previous or the new checkpoint.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut tmp_path = PathBuf::from(path);
+        tmp_path.as_mut_os_string().push(".tmp");
+
+        let mut file = File::create(&tmp_path)?;
+        write!(
+            file,
+            "door_id={}\nalgorithm={}\nrule={}\nnext_index={}\npassword={}\n",
+            self.door_id, self.algorithm, self.rule, self.next_index, self.password
+        )?;
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("day_05_checkpoint_{}_{name}", std::process::id()))
+    }
+
+    fn get_checkpoint() -> Checkpoint {
+        Checkpoint {
+            door_id: String::from("abc"),
+            algorithm: String::from("md5"),
+            rule: PasswordRule::advanced(),
+            next_index: 5017309,
+            password: String::from("_5______"),
+        }
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = get_path("save_load");
+        let checkpoint = get_checkpoint();
+
+        checkpoint.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), Some(checkpoint));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing() {
+        assert_eq!(Checkpoint::load(&get_path("missing")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_save_leaves_no_tmp() {
+        let path = get_path("no_tmp");
+        get_checkpoint().save(&path).unwrap();
+
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        assert!(!Path::new(&tmp_path).exists());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        assert!(Checkpoint::parse("door_id=abc\nalgorithm=md5\nrule=length=8\nnext_").is_err());
+        // Written before the algorithm was recorded
+        assert!(
+            Checkpoint::parse("door_id=abc\nrule=length=1\nnext_index=1\npassword=_\n").is_err()
+        );
+        assert!(Checkpoint::parse("").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(
+            Checkpoint::parse(
+                "door_id=abc\nalgorithm=md5\nrule=speed=1\nnext_index=1\npassword=_\n"
+            )
+            .is_err()
+        );
+        assert!(
+            Checkpoint::parse(
+                "door_id=abc\nalgorithm=md5\nrule=length=1\nnext_index=x\npassword=_\n"
+            )
+            .is_err()
+        );
+        assert!(
+            Checkpoint::parse(
+                "door_id=abc\nalgorithm=md5\nrule=length=0\nnext_index=1\npassword=_\n"
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,176 +1,509 @@
-const PASSWORD_LENGTH: usize = 8;
-const HEX_DIGITS: &[char] = &[
-    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
-];
-const ASCII_DIGIT_0: u8 = 48;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::puzzle::checkpoint::Checkpoint;
+use crate::puzzle::hash_algorithm::{HashAlgorithm, Md5};
+use crate::puzzle::miner::Miner;
+use crate::puzzle::password_rule::{EMPTY, Password, PasswordRule};
+
+// Hashes searched without a hit before progress is saved anyway
+const CHECKPOINT_INTERVAL: u64 = 10_000_000;
+
+// Snapshot reported to the observer whenever a password character is found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    // Partial password, EMPTY for positions not found yet
+    pub password: String,
+    pub found: usize,
+    pub hashes: u64,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    fn new(pwd: &[char], hashes: u64, elapsed: Duration) -> Self {
+        Self {
+            password: pwd.iter().collect(),
+            found: pwd.iter().filter(|c| *c != &EMPTY).count(),
+            hashes,
+            elapsed,
+        }
+    }
+
+    pub fn hashes_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
 
-pub struct Hacker {
+        if seconds > 0.0 {
+            self.hashes as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    // Assumes every remaining character takes as long as the found ones on average
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        if self.found == 0 {
+            return None;
+        }
+
+        let missing = self.password.chars().count() - self.found;
+        Some(self.elapsed / self.found as u32 * missing as u32)
+    }
+}
+
+// Works with any digest long enough for the nibbles of the password rule
+pub struct Hacker<H: HashAlgorithm = Md5> {
     door_id: String,
+    algorithm: H,
+    checkpoint: Option<PathBuf>,
 }
 
 impl Hacker {
     pub fn new(door_id: &str) -> Self {
+        Self::with_algorithm(door_id, Md5)
+    }
+}
+
+impl<H: HashAlgorithm + Clone> Hacker<H> {
+    pub fn with_algorithm(door_id: &str, algorithm: H) -> Self {
         Self {
             door_id: door_id.to_string(),
+            algorithm,
+            checkpoint: None,
         }
     }
 
-    #[inline]
-    fn number_to_u8(mut number: usize, array: &mut [u8; 8]) -> usize {
-        let mut index = 0;
-
-        loop {
-            if number == 0 {
-                break;
-            }
+    // Progress is saved to `path` whenever a character is found and at least every
+    // CHECKPOINT_INTERVAL hashes, it is restored on the next run. Checkpoint of another door id
+    // or rule, or an unreadable one, is ignored.
+    pub fn with_checkpoint(mut self, path: &Path) -> Self {
+        self.checkpoint = Some(path.to_path_buf());
+        self
+    }
 
-            let code = (number % 10) as u8;
-            number /= 10;
+    // Returns password found so far and the first index to search
+    fn resume<'a>(&self, rule: &'a PasswordRule) -> (Password<'a>, u64) {
+        let checkpoint = self
+            .checkpoint
+            .as_deref()
+            .and_then(|path| Checkpoint::load(path).ok().flatten())
+            .filter(|checkpoint| {
+                checkpoint.door_id == self.door_id
+                    && checkpoint.algorithm == self.algorithm.id()
+                    && &checkpoint.rule == rule
+            });
+
+        checkpoint
+            .and_then(|checkpoint| {
+                Password::from_partial(rule, &checkpoint.password)
+                    .map(|pwd| (pwd, checkpoint.next_index))
+            })
+            .unwrap_or_else(|| (Password::new(rule), 0))
+    }
 
-            array[index] = code + ASCII_DIGIT_0;
-            index += 1;
+    fn save(
+        &self,
+        rule: &PasswordRule,
+        pwd: &Password,
+        next_index: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(path) = &self.checkpoint {
+            let checkpoint = Checkpoint {
+                door_id: self.door_id.clone(),
+                algorithm: self.algorithm.id(),
+                rule: rule.clone(),
+                next_index,
+                password: pwd.to_string(),
+            };
+
+            checkpoint
+                .save(path)
+                .map_err(|err| format!("Failed to save checkpoint {path:?} [{err}]"))?;
         }
 
-        array[..index].reverse();
-        index
+        Ok(())
+    }
+
+    pub fn crack_password(&self) -> Result<String, Box<dyn Error>> {
+        self.crack(&PasswordRule::simple())
     }
 
-    pub fn crack_password(&self) -> String {
-        let mut pwd = String::with_capacity(PASSWORD_LENGTH);
+    pub fn crack_password_with<F>(&self, observer: F) -> Result<String, Box<dyn Error>>
+    where
+        F: FnMut(&Progress),
+    {
+        self.crack_with(&PasswordRule::simple(), observer)
+    }
 
-        // Buffer for whole data to hash
-        let mut buffer = [0_u8; 32];
-        let mut buffer_len;
+    pub fn crack_password_advanced(&self) -> Result<String, Box<dyn Error>> {
+        self.crack(&PasswordRule::advanced())
+    }
 
-        // Buffer for number only
-        let mut number: usize = 1;
-        let mut buffer_number = [0_u8; 8];
-        let mut buffer_number_len;
+    pub fn crack_password_advanced_with<F>(&self, observer: F) -> Result<String, Box<dyn Error>>
+    where
+        F: FnMut(&Progress),
+    {
+        self.crack_with(&PasswordRule::advanced(), observer)
+    }
 
-        // Insert door ir, it is same for all iterations
-        buffer[..self.door_id.len()].copy_from_slice(self.door_id.as_bytes());
+    pub fn crack(&self, rule: &PasswordRule) -> Result<String, Box<dyn Error>> {
+        self.crack_with(rule, |_progress| {})
+    }
 
-        while pwd.len() != PASSWORD_LENGTH {
-            // Prepare buffer, door id is already filled
-            // Convert manually number to chars
-            buffer_number_len = Self::number_to_u8(number, &mut buffer_number);
-            buffer_len = self.door_id.len() + buffer_number_len;
+    // Observer is called whenever the password changes. Returns partial password if the whole
+    // index range is searched without completing it. Fails if the rule does not fit the digest or
+    // the checkpoint can't be saved.
+    pub fn crack_with<F>(
+        &self,
+        rule: &PasswordRule,
+        mut observer: F,
+    ) -> Result<String, Box<dyn Error>>
+    where
+        F: FnMut(&Progress),
+    {
+        // Digest size is only known from a digest
+        let digest_nibbles = self.algorithm.hash(self.door_id.as_bytes()).as_ref().len() * 2;
+        rule.validate(digest_nibbles)?;
+
+        let start = Instant::now();
+        let (mut pwd, first) = self.resume(rule);
+
+        if pwd.found() > 0 {
+            observer(&Progress::new(pwd.as_chars(), first, start.elapsed()));
+        }
 
-            buffer[self.door_id.len()..buffer_len]
-                .copy_from_slice(&buffer_number[..buffer_number_len]);
+        if pwd.is_complete() {
+            return Ok(pwd.to_string());
+        }
 
-            // Calculate hash
-            let digest = md5::compute(&buffer[..buffer_len]);
+        let miner = Miner::with_algorithm(&self.door_id, rule.condition(), self.algorithm.clone());
+        let mut saved = first;
 
-            // Find five zeros
-            // Digest is [u8; 16]
-            // byte 0 -> two zeros
-            // byte 1 -> two zeros
-            // byte 2 -> one zero (higher byte)
-            let five_zeros = digest.0[0] == 0 && digest.0[1] == 0 && (digest.0[2] & 0xF0) == 0;
+        for batch in miner.batches_from(first) {
+            // Hits are ordered by index so the first occurence is the lowest one
+            for (index, digest) in batch.hits {
+                if pwd.apply(digest.as_ref()) {
+                    saved = index.saturating_add(1);
+                    self.save(rule, &pwd, saved)?;
+                    observer(&Progress::new(pwd.as_chars(), saved, start.elapsed()));
 
-            if five_zeros {
-                // 6th number is lower part of 3rd byte
-                let code = (digest.0[2] & 0x0F) as usize;
-                let code = HEX_DIGITS[code];
-                pwd.push(code);
+                    if pwd.is_complete() {
+                        return Ok(pwd.to_string());
+                    }
+                }
             }
 
-            number += 1;
+            match batch.next {
+                Some(next) if next - saved >= CHECKPOINT_INTERVAL => {
+                    saved = next;
+                    self.save(rule, &pwd, saved)?;
+                }
+                Some(_next) => {}
+                None => break,
+            }
         }
 
-        pwd
+        Ok(pwd.to_string())
     }
+}
 
-    pub fn crack_password_advanced(&self) -> String {
-        // _ means no pwd yet in the position
-        const EMPTY: char = '_';
-        let mut pwd = [EMPTY; PASSWORD_LENGTH];
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
 
-        // Buffer for whole data to hash
-        let mut buffer = [0_u8; 32];
-        let mut buffer_len;
+    use super::*;
+    use crate::puzzle::hash_algorithm::StretchedMd5;
 
-        // Buffer for number only
-        let mut number: usize = 1;
-        let mut buffer_number = [0_u8; 8];
-        let mut buffer_number_len;
+    #[test]
+    fn test_crack_password() {
+        assert_eq!(&Hacker::new("abc").crack_password().unwrap(), "18f47a30");
+    }
 
-        // Insert door ir, it is same for all iterations
-        buffer[..self.door_id.len()].copy_from_slice(self.door_id.as_bytes());
+    #[test]
+    fn test_crack_password_advanced() {
+        assert_eq!(
+            &Hacker::new("abc").crack_password_advanced().unwrap(),
+            "05ace8e3"
+        );
+    }
 
-        while pwd.iter().any(|c| c == &EMPTY) {
-            // Prepare buffer, door id is already filled
-            // Convert manually number to chars
-            buffer_number_len = Self::number_to_u8(number, &mut buffer_number);
-            buffer_len = self.door_id.len() + buffer_number_len;
+    #[test]
+    fn test_crack_password_with() {
+        let mut updates = Vec::new();
+        Hacker::new("abc")
+            .crack_password_with(|progress| updates.push(progress.clone()))
+            .unwrap();
+
+        assert_eq!(updates.len(), 8);
+        assert_eq!(updates[0].password, "1_______");
+        assert_eq!(updates[0].hashes, 3231930);
+        assert_eq!(updates[1].password, "18______");
+        assert_eq!(updates[7].password, "18f47a30");
+        assert_eq!(updates[7].found, 8);
+    }
 
-            buffer[self.door_id.len()..buffer_len]
-                .copy_from_slice(&buffer_number[..buffer_number_len]);
+    #[test]
+    fn test_crack_password_advanced_with() {
+        let mut updates = Vec::new();
+        Hacker::new("abc")
+            .crack_password_advanced_with(|progress| updates.push(progress.clone()))
+            .unwrap();
+
+        assert_eq!(updates.len(), 8);
+        assert_eq!(updates[0].password, "_5______");
+        assert_eq!(updates[7].password, "05ace8e3");
+    }
+
+    #[test]
+    fn test_crack_custom_rule() {
+        // 7th hex digit of the first two hits
+        let rule = PasswordRule {
+            length: 2,
+            value: 6,
+            ..PasswordRule::simple()
+        };
+
+        assert_eq!(Hacker::new("abc").crack(&rule).unwrap(), "5f");
+    }
 
-            // Calculate hash
-            let digest = md5::compute(&buffer[..buffer_len]);
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new(&['a', 'b', EMPTY, EMPTY], 1000, Duration::from_secs(4));
 
-            // Find five zeros
-            // Digest is [u8; 16]
-            // byte 0 -> two zeros
-            // byte 1 -> two zeros
-            // byte 2 -> one zero (higher byte)
-            let five_zeros =
-                digest.0[0] == 0 && digest.0[1] == 0 && ((digest.0[2] & 0xF0) >> 4) == 0;
+        assert_eq!(progress.found, 2);
+        assert_eq!(progress.hashes_per_second(), 250.0);
+        assert_eq!(progress.estimated_remaining(), Some(Duration::from_secs(4)));
+    }
 
-            if five_zeros {
-                // 6th number is lower part of 3rd byte
-                let position = (digest.0[2] & 0x0F) as usize;
+    #[test]
+    fn test_progress_nothing_found() {
+        let progress = Progress::new(&[EMPTY; 4], 0, Duration::ZERO);
 
-                // Write only on first occurence + skip invalid position
-                if position < PASSWORD_LENGTH && pwd[position] == EMPTY {
-                    // 7th number is higher part of 4th byte
-                    let code = ((digest.0[3] & 0xF0) >> 4) as usize;
-                    let code = HEX_DIGITS[code];
+        assert_eq!(progress.hashes_per_second(), 0.0);
+        assert_eq!(progress.estimated_remaining(), None);
+    }
 
-                    pwd[position] = code;
-                }
-            }
+    fn get_checkpoint_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("day_05_hacker_{}_{name}", std::process::id()))
+    }
 
-            number += 1;
+    #[test]
+    fn test_checkpoint_resume() {
+        let path = get_checkpoint_path("resume");
+
+        // Pretend that the first 3 characters were found by a killed run
+        Checkpoint {
+            door_id: String::from("abc"),
+            algorithm: String::from("md5"),
+            rule: PasswordRule::simple(),
+            next_index: 5278569,
+            password: String::from("18f_____"),
         }
+        .save(&path)
+        .unwrap();
 
-        pwd.iter().collect::<String>()
-    }
-}
+        let mut updates = Vec::new();
+        let pwd = Hacker::new("abc")
+            .with_checkpoint(&path)
+            .crack_password_with(|progress| updates.push(progress.clone()))
+            .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert_eq!(pwd, "18f47a30");
+        assert_eq!(updates.len(), 1 + 8 - 3);
+        assert_eq!(updates[0].password, "18f_____");
+
+        let checkpoint = Checkpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.password, "18f47a30");
+
+        std::fs::remove_file(&path).unwrap();
+    }
 
     #[test]
-    fn test_crack_password() {
-        assert_eq!(&Hacker::new("abc").crack_password(), "18f47a30");
+    fn test_checkpoint_advanced() {
+        let path = get_checkpoint_path("advanced");
+        let hacker = Hacker::new("abc").with_checkpoint(&path);
+
+        assert_eq!(hacker.crack_password_advanced().unwrap(), "05ace8e3");
+
+        // Finished checkpoint answers immediately
+        let checkpoint = Checkpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.rule, PasswordRule::advanced());
+        assert_eq!(checkpoint.password, "05ace8e3");
+        assert_eq!(hacker.crack_password_advanced().unwrap(), "05ace8e3");
+
+        std::fs::remove_file(&path).unwrap();
     }
 
     #[test]
-    fn test_crack_password_advanced() {
-        assert_eq!(&Hacker::new("abc").crack_password_advanced(), "05ace8e3");
+    fn test_checkpoint_ignored() {
+        let path = get_checkpoint_path("ignored");
+
+        // Different rule must not leak into the simple password
+        Checkpoint {
+            door_id: String::from("abc"),
+            algorithm: String::from("md5"),
+            rule: PasswordRule::advanced(),
+            next_index: 100_000_000,
+            password: String::from("zzzzzzzz"),
+        }
+        .save(&path)
+        .unwrap();
+
+        assert_eq!(
+            Hacker::new("abc")
+                .with_checkpoint(&path)
+                .crack_password()
+                .unwrap(),
+            "18f47a30"
+        );
+
+        std::fs::remove_file(&path).unwrap();
     }
 
     #[test]
-    fn test_number_to_u8() {
-        let mut array = [0_u8; 8];
+    fn test_checkpoint_other_algorithm() {
+        let path = get_checkpoint_path("other_algorithm");
+        let rule = PasswordRule {
+            length: 1,
+            zeros: 3,
+            ..PasswordRule::simple()
+        };
+
+        // Nothing would be found past u64::MAX if the MD5 checkpoint was taken over
+        Checkpoint {
+            door_id: String::from("abc"),
+            algorithm: Md5.id(),
+            rule: rule.clone(),
+            next_index: u64::MAX,
+            password: String::from("_"),
+        }
+        .save(&path)
+        .unwrap();
+
+        let stretched = Hacker::with_algorithm("abc", StretchedMd5::new(1));
+        let expected = stretched.crack(&rule).unwrap();
 
-        Hacker::number_to_u8(123, &mut array);
+        assert_ne!(expected, "_");
         assert_eq!(
-            array,
-            [
-                1 + ASCII_DIGIT_0,
-                2 + ASCII_DIGIT_0,
-                3 + ASCII_DIGIT_0,
-                0,
-                0,
-                0,
-                0,
-                0
-            ]
+            stretched.with_checkpoint(&path).crack(&rule).unwrap(),
+            expected
+        );
+
+        let checkpoint = Checkpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.algorithm, "md5x1");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // Toy 4 byte digest, first three bytes are always zero
+    #[derive(Clone)]
+    struct ShortDigest;
+
+    impl HashAlgorithm for ShortDigest {
+        type Digest = [u8; 4];
+
+        fn id(&self) -> String {
+            String::from("short")
+        }
+
+        fn hash(&self, data: &[u8]) -> Self::Digest {
+            let sum = data.iter().map(|byte| *byte as usize).sum::<usize>();
+            [0, 0, (sum % 8) as u8, (sum % 16) as u8 * 16]
+        }
+    }
+
+    #[test]
+    fn test_rule_outside_digest() {
+        let rule = PasswordRule {
+            value: 40,
+            ..PasswordRule::simple()
+        };
+
+        assert!(Hacker::new("abc").crack(&rule).is_err());
+        assert!(
+            Hacker::new("abc")
+                .crack(&PasswordRule {
+                    zeros: 40,
+                    ..PasswordRule::simple()
+                })
+                .is_err()
+        );
+
+        // Advanced preset needs nibble 6, the toy digest has 8 of them
+        let short = Hacker::with_algorithm("", ShortDigest);
+        assert!(short.crack(&PasswordRule { value: 8, ..rule }).is_err());
+    }
+
+    #[test]
+    fn test_with_algorithm() {
+        // Index 0..7 gives digit sums 48..55 => positions 0..7 and values 0..7
+        let hacker = Hacker::with_algorithm("", ShortDigest);
+
+        assert_eq!(hacker.crack_password().unwrap(), "01234567");
+        assert_eq!(hacker.crack_password_advanced().unwrap(), "01234567");
+    }
+
+    // Toy digest with a single hit at HIT_INDEX, reads the checkpoint when hashing it
+    const HIT_INDEX: u64 = 3 * CHECKPOINT_INTERVAL;
+
+    #[derive(Clone)]
+    struct Probe {
+        path: PathBuf,
+        next_index: Arc<Mutex<Option<u64>>>,
+    }
+
+    impl HashAlgorithm for Probe {
+        type Digest = [u8; 4];
+
+        fn id(&self) -> String {
+            String::from("probe")
+        }
+
+        fn hash(&self, data: &[u8]) -> Self::Digest {
+            if data != HIT_INDEX.to_string().as_bytes() {
+                return [0xff; 4];
+            }
+
+            let checkpoint = Checkpoint::load(&self.path).unwrap().unwrap();
+            *self.next_index.lock().unwrap() = Some(checkpoint.next_index);
+            [0, 0, 0x07, 0]
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_interval() {
+        let path = get_checkpoint_path("interval");
+        let probe = Probe {
+            path: path.clone(),
+            next_index: Arc::new(Mutex::new(None)),
+        };
+        let rule = PasswordRule {
+            length: 1,
+            ..PasswordRule::simple()
+        };
+
+        let pwd = Hacker::with_algorithm("", probe.clone())
+            .with_checkpoint(&path)
+            .crack(&rule)
+            .unwrap();
+        assert_eq!(pwd, "7");
+
+        // Saved on the way although nothing was found yet
+        let next_index = probe.next_index.lock().unwrap().unwrap();
+        assert!((CHECKPOINT_INTERVAL..=HIT_INDEX).contains(&next_index));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_save_failed() {
+        let path = get_checkpoint_path("missing").join("checkpoint");
+
+        assert!(
+            Hacker::new("abc")
+                .with_checkpoint(&path)
+                .crack_password()
+                .is_err()
         );
     }
 }
@@ -0,0 +1,142 @@
+#[cfg(feature = "multi_lane")]
+use crate::puzzle::md5_lanes;
+
+const HEX_LOWER: &[u8] = b"0123456789abcdef";
+// Messages hashed together by `hash_lanes`
+pub const LANES: usize = 8;
+
+// Digest used by the miner, shared between hashing threads
+pub trait HashAlgorithm: Sync {
+    type Digest: AsRef<[u8]> + Send;
+
+    // Tells checkpoints of different algorithms or parameters apart, e.g. "md5x2016"
+    fn id(&self) -> String;
+
+    fn hash(&self, data: &[u8]) -> Self::Digest;
+
+    // Algorithms able to hash several messages at once override this, by default messages are
+    // hashed one by one
+    #[inline]
+    fn hash_lanes(&self, data: &[&[u8]; LANES]) -> [Self::Digest; LANES] {
+        std::array::from_fn(|lane| self.hash(data[lane]))
+    }
+}
+
+// Plain MD5, the default for all day 5 puzzles
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Md5;
+
+impl HashAlgorithm for Md5 {
+    type Digest = [u8; 16];
+
+    fn id(&self) -> String {
+        String::from("md5")
+    }
+
+    #[inline]
+    fn hash(&self, data: &[u8]) -> Self::Digest {
+        md5::compute(data).0
+    }
+
+    // Multi-lane backend handles single block messages only, longer ones go to the scalar code
+    #[cfg(feature = "multi_lane")]
+    #[inline]
+    fn hash_lanes(&self, data: &[&[u8]; LANES]) -> [Self::Digest; LANES] {
+        md5_lanes::compute(data)
+            .unwrap_or_else(|| std::array::from_fn(|lane| self.hash(data[lane])))
+    }
+}
+
+// Key stretching: MD5 of the data, then MD5 of the lowercase hex digest `rounds` more times
+#[derive(Debug, Clone, Copy)]
+pub struct StretchedMd5 {
+    rounds: usize,
+}
+
+impl StretchedMd5 {
+    pub fn new(rounds: usize) -> Self {
+        Self { rounds }
+    }
+
+    #[inline]
+    fn to_hex(digest: &[u8; 16], hex: &mut [u8; 32]) {
+        for (index, byte) in digest.iter().enumerate() {
+            hex[2 * index] = HEX_LOWER[(byte >> 4) as usize];
+            hex[2 * index + 1] = HEX_LOWER[(byte & 0x0F) as usize];
+        }
+    }
+}
+
+impl HashAlgorithm for StretchedMd5 {
+    type Digest = [u8; 16];
+
+    fn id(&self) -> String {
+        format!("md5x{}", self.rounds)
+    }
+
+    fn hash(&self, data: &[u8]) -> Self::Digest {
+        let mut digest = md5::compute(data).0;
+        let mut hex = [0_u8; 32];
+
+        for _ in 0..self.rounds {
+            Self::to_hex(&digest, &mut hex);
+            digest = md5::compute(hex).0;
+        }
+
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(Md5.hash(b"abc3231929"), md5::compute("abc3231929").0);
+    }
+
+    #[test]
+    fn test_md5_lanes() {
+        // Last message is too long for a single block
+        let long = "x".repeat(100);
+        let mut data: [&[u8]; LANES] = std::array::from_fn(|_| b"abc3231929".as_slice());
+        data[LANES - 1] = long.as_bytes();
+
+        let digests = Md5.hash_lanes(&data);
+
+        assert_eq!(digests[0], md5::compute("abc3231929").0);
+        assert_eq!(digests[LANES - 1], md5::compute(&long).0);
+    }
+
+    #[test]
+    fn test_stretched_md5_no_rounds() {
+        assert_eq!(StretchedMd5::new(0).hash(b"abc0"), Md5.hash(b"abc0"));
+    }
+
+    #[test]
+    fn test_stretched_md5() {
+        let once = format!("{:x}", md5::compute("abc0"));
+        let twice = md5::compute(once.as_bytes());
+
+        assert_eq!(once, "577571be4de9dcce85a041ba0410f29f");
+        assert_eq!(StretchedMd5::new(1).hash(b"abc0"), twice.0);
+    }
+
+    #[test]
+    fn test_id() {
+        assert_eq!(Md5.id(), "md5");
+        assert_eq!(StretchedMd5::new(2016).id(), "md5x2016");
+    }
+
+    #[test]
+    fn test_stretched_md5_2016() {
+        // Known key stretching example, 2016 extra rounds
+        let digest = StretchedMd5::new(2016).hash(b"abc0");
+
+        assert_eq!(
+            format!("{:x}", md5::Digest(digest)),
+            "a107ff634856bb300138cac6568c0f24"
+        );
+    }
+}
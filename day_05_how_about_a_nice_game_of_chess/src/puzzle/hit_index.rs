@@ -0,0 +1,297 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use hex::FromHex;
+use serde::{Deserialize, Serialize};
+
+use crate::puzzle::hash_algorithm::{HashAlgorithm, Md5};
+use crate::puzzle::miner::Miner;
+use crate::puzzle::password_rule::{Password, PasswordRule};
+
+// Hits hashed again on load
+const VERIFIED_HITS: usize = 3;
+
+// Index is always built with plain MD5
+type Digest = <Md5 as HashAlgorithm>::Digest;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Hit {
+    pub index: u64,
+    // Lowercase hex MD5 digest
+    pub digest: String,
+}
+
+// All hits of a door id up to the point where both passwords are known
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct HitIndex {
+    pub door_id: String,
+    pub hits: Vec<Hit>,
+}
+
+impl HitIndex {
+    // Single scan for both preset passwords, ends once both of them are complete
+    pub fn build(door_id: &str) -> Self {
+        let (simple, advanced) = (PasswordRule::simple(), PasswordRule::advanced());
+        let mut pwd = Password::new(&simple);
+        let mut pwd_advanced = Password::new(&advanced);
+        let mut hits = Vec::new();
+
+        // Both presets share the zero prefix
+        let miner = Miner::new(door_id, simple.condition());
+
+        for (index, digest) in miner.iter() {
+            hits.push(Hit {
+                index,
+                digest: hex::encode(digest),
+            });
+
+            pwd.apply(&digest);
+            pwd_advanced.apply(&digest);
+
+            if pwd.is_complete() && pwd_advanced.is_complete() {
+                break;
+            }
+        }
+
+        Self {
+            door_id: door_id.to_string(),
+            hits,
+        }
+    }
+
+    // Returns None if there is no index yet. Hashes the first hits again, an index edited by
+    // hand or mixed up with another door id fails.
+    pub fn load(path: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let index: Self = serde_json::from_str(&content)?;
+
+        index.verify()?;
+        Ok(Some(index))
+    }
+
+    fn verify(&self) -> Result<(), Box<dyn Error>> {
+        if self
+            .hits
+            .windows(2)
+            .any(|pair| pair[0].index >= pair[1].index)
+        {
+            return Err(format!("Hits of '{}' are not ordered by index", self.door_id).into());
+        }
+
+        for hit in self.hits.iter().take(VERIFIED_HITS) {
+            let digest = hex::encode(Md5.hash(format!("{}{}", self.door_id, hit.index).as_bytes()));
+
+            if hit.digest != digest {
+                return Err(format!(
+                    "Digest '{}' at index {} does not belong to '{}'",
+                    hit.digest, hit.index, self.door_id
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Written to a temporary file and renamed, a killed run never leaves half of the index
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut tmp_path = PathBuf::from(path);
+        tmp_path.as_mut_os_string().push(".tmp");
+
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn digests(&self) -> Result<Vec<Digest>, Box<dyn Error>> {
+        self.hits
+            .iter()
+            .map(|hit| {
+                Digest::from_hex(&hit.digest).map_err(|err| -> Box<dyn Error> {
+                    format!(
+                        "Invalid digest '{}' at index {} [{err}]",
+                        hit.digest, hit.index
+                    )
+                    .into()
+                })
+            })
+            .collect()
+    }
+
+    pub fn password(&self) -> Result<String, Box<dyn Error>> {
+        self.password_with(&PasswordRule::simple())
+    }
+
+    pub fn password_advanced(&self) -> Result<String, Box<dyn Error>> {
+        self.password_with(&PasswordRule::advanced())
+    }
+
+    // Index holds only hits of the presets, rules asking for fewer zeros would miss hashes.
+    // Index built for the presets may also end before a custom password is complete.
+    pub fn password_with(&self, rule: &PasswordRule) -> Result<String, Box<dyn Error>> {
+        let zeros = PasswordRule::simple().zeros;
+
+        if rule.zeros < zeros {
+            return Err(format!(
+                "Index has only hits with {zeros} zeros, rule needs {}",
+                rule.zeros
+            )
+            .into());
+        }
+
+        rule.validate(size_of::<Digest>() * 2)?;
+        let digests = self.digests()?;
+
+        rule.password_from_digests(digests.iter().map(|digest| digest.as_slice()))
+            .ok_or_else(|| format!("Index of '{}' has too few hits", self.door_id).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let index = HitIndex::build("abc");
+
+        assert_eq!(index.door_id, "abc");
+        assert_eq!(
+            index.hits[0],
+            Hit {
+                index: 3231929,
+                digest: String::from("00000155f8105dff7f56ee10fa9b9abd")
+            }
+        );
+        assert_eq!(index.password().unwrap(), "18f47a30");
+        assert_eq!(index.password_advanced().unwrap(), "05ace8e3");
+    }
+
+    #[test]
+    fn test_password_with() {
+        let index = HitIndex::build("abc");
+        let rule = PasswordRule {
+            length: 2,
+            value: 6,
+            ..PasswordRule::simple()
+        };
+
+        assert_eq!(index.password_with(&rule).unwrap(), "5f");
+        assert!(
+            index
+                .password_with(&PasswordRule { zeros: 4, ..rule })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join(format!("day_05_index_{}.json", std::process::id()));
+        let index = HitIndex {
+            door_id: String::from("abc"),
+            hits: vec![Hit {
+                index: 3231929,
+                digest: String::from("00000155f8105dff7f56ee10fa9b9abd"),
+            }],
+        };
+
+        index.save(&path).unwrap();
+        assert_eq!(HitIndex::load(&path).unwrap(), Some(index));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing() {
+        let path =
+            std::env::temp_dir().join(format!("day_05_index_missing_{}.json", std::process::id()));
+
+        assert_eq!(HitIndex::load(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_load_mismatch() {
+        let path =
+            std::env::temp_dir().join(format!("day_05_index_mismatch_{}.json", std::process::id()));
+        let hit = |index, digest: &str| Hit {
+            index,
+            digest: String::from(digest),
+        };
+
+        // Index of "abc" claimed by another door id
+        let mut index = HitIndex {
+            door_id: String::from("abd"),
+            hits: vec![hit(3231929, "00000155f8105dff7f56ee10fa9b9abd")],
+        };
+        index.save(&path).unwrap();
+        assert!(HitIndex::load(&path).is_err());
+
+        // Right door id, forged digest
+        index.door_id = String::from("abc");
+        index.hits[0].digest = String::from("00000f55f8105dff7f56ee10fa9b9abd");
+        index.save(&path).unwrap();
+        assert!(HitIndex::load(&path).is_err());
+
+        // Same hit twice
+        index.hits = vec![
+            hit(3231929, "00000155f8105dff7f56ee10fa9b9abd"),
+            hit(3231929, "00000155f8105dff7f56ee10fa9b9abd"),
+        ];
+        index.save(&path).unwrap();
+        assert!(HitIndex::load(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_too_few_hits() {
+        let index = HitIndex {
+            door_id: String::from("abc"),
+            hits: vec![Hit {
+                index: 3231929,
+                digest: String::from("00000155f8105dff7f56ee10fa9b9abd"),
+            }],
+        };
+
+        assert!(index.password().is_err());
+        assert!(index.password_advanced().is_err());
+    }
+
+    #[test]
+    fn test_invalid_digest() {
+        let index = HitIndex {
+            door_id: String::from("abc"),
+            hits: vec![Hit {
+                index: 1,
+                digest: String::from("xyz"),
+            }],
+        };
+
+        assert!(index.password().is_err());
+
+        // Valid hex, but too short for the nibbles of the rule
+        let index = HitIndex {
+            door_id: String::from("abc"),
+            hits: vec![Hit {
+                index: 1,
+                digest: String::from("000001"),
+            }],
+        };
+
+        assert!(index.password().is_err());
+        assert!(
+            index
+                .password_with(&PasswordRule {
+                    value: 40,
+                    ..PasswordRule::simple()
+                })
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::puzzle::hacker::Progress;
+use crate::puzzle::password_rule::EMPTY;
+
+const FRAME: Duration = Duration::from_millis(80);
+const HEX_GLYPHS: &[u8] = b"0123456789abcdef";
+
+// Redraws the partial password on one terminal line while cracking runs in the background.
+// Positions not found yet keep flickering with random hex digits.
+pub struct LiveDisplay;
+
+impl LiveDisplay {
+    // Password `length` is shown before the first character is found. Returns whatever `crack`
+    // returns.
+    pub fn run<F, T>(length: usize, crack: F) -> T
+    where
+        F: FnOnce(&mut dyn FnMut(&Progress)) -> T + Send,
+        T: Send,
+    {
+        let latest: Mutex<Option<Progress>> = Mutex::new(None);
+        let done = AtomicBool::new(false);
+
+        let result = thread::scope(|scope| {
+            let worker = scope.spawn(|| {
+                let result = crack(&mut |progress| {
+                    *latest.lock().expect("Progress lock poisoned") = Some(progress.clone());
+                });
+
+                done.store(true, Ordering::Release);
+                result
+            });
+
+            let mut frame = 0;
+            while !done.load(Ordering::Acquire) {
+                let progress = latest.lock().expect("Progress lock poisoned").clone();
+                Self::draw(&Self::render(progress.as_ref(), length, frame));
+
+                frame += 1;
+                thread::sleep(FRAME);
+            }
+
+            worker.join().expect("Cracking thread panicked")
+        });
+
+        let progress = latest.into_inner().expect("Progress lock poisoned");
+        Self::draw(&Self::render(progress.as_ref(), length, 0));
+        println!();
+
+        result
+    }
+
+    fn draw(line: &str) {
+        // Carriage return keeps redrawing the same line, ignore closed terminal
+        let mut stdout = io::stdout().lock();
+        let _ = write!(stdout, "\r{line}");
+        let _ = stdout.flush();
+    }
+
+    fn render(progress: Option<&Progress>, length: usize, frame: usize) -> String {
+        let password = match progress {
+            Some(progress) => progress.password.clone(),
+            None => EMPTY.to_string().repeat(length),
+        };
+
+        let scrambled = password
+            .chars()
+            .enumerate()
+            .map(|(position, c)| match c {
+                EMPTY => Self::glyph(frame, position),
+                c => c,
+            })
+            .collect::<String>();
+
+        match progress {
+            Some(progress) => {
+                let eta = progress
+                    .estimated_remaining()
+                    .map(|eta| format!("{:.1}s", eta.as_secs_f64()))
+                    .unwrap_or_else(|| String::from("?"));
+
+                format!(
+                    "[ {scrambled} ]  hashes: {:>12}  rate: {:>6.2} MH/s  eta: {eta:<8}",
+                    progress.hashes,
+                    progress.hashes_per_second() / 1_000_000.0
+                )
+            }
+            None => format!(
+                "[ {scrambled} ]  hashes: {:>12}  rate: {:>6} MH/s  eta: {:<8}",
+                "?", "?", "?"
+            ),
+        }
+    }
+
+    // Cheap pseudo random hex digit, only needs to look noisy
+    fn glyph(frame: usize, position: usize) -> char {
+        let mut x = (frame as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (position as u64 + 1);
+        x ^= x >> 29;
+        x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x ^= x >> 32;
+
+        HEX_GLYPHS[(x % 16) as usize] as char
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let progress = Progress {
+            password: String::from("18f47a30"),
+            found: 8,
+            hashes: 2_000_000,
+            elapsed: Duration::from_secs(2),
+        };
+
+        assert_eq!(
+            LiveDisplay::render(Some(&progress), 8, 3),
+            "[ 18f47a30 ]  hashes:      2000000  rate:   1.00 MH/s  eta: 0.0s    "
+        );
+    }
+
+    #[test]
+    fn test_render_scrambles_empty() {
+        let progress = Progress {
+            password: String::from("18______"),
+            found: 2,
+            hashes: 10,
+            elapsed: Duration::from_secs(1),
+        };
+
+        let line = LiveDisplay::render(Some(&progress), 8, 7);
+        let password = &line[2..10];
+
+        assert!(password.starts_with("18"));
+        assert!(password.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(line.ends_with("eta: 3.0s    "));
+    }
+
+    #[test]
+    fn test_render_no_progress() {
+        let line = LiveDisplay::render(None, 8, 0);
+
+        assert!(line[2..10].chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(line.contains("eta: ?"));
+    }
+
+    #[test]
+    fn test_run() {
+        let password = LiveDisplay::run(2, |observer| {
+            observer(&Progress {
+                password: String::from("ab"),
+                found: 2,
+                hashes: 1,
+                elapsed: Duration::from_millis(1),
+            });
+            String::from("ab")
+        });
+
+        assert_eq!(password, "ab");
+    }
+}
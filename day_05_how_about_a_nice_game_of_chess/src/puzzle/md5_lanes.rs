@@ -0,0 +1,188 @@
+use crate::puzzle::hash_algorithm::LANES;
+
+// Longest message fitting into one 64 byte block next to the 0x80 marker and 64 bit length
+pub const MAX_MESSAGE_LENGTH: usize = 55;
+
+const INIT: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+// floor(abs(sin(i + 1)) * 2^32)
+const CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, //
+    0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501, //
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, //
+    0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821, //
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, //
+    0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8, //
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, //
+    0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a, //
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, //
+    0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, //
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, //
+    0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665, //
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, //
+    0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1, //
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, //
+    0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+// One u32 per lane, every operation runs on all lanes in a plain loop which the compiler turns
+// into SIMD instructions
+type Lanes = [u32; LANES];
+
+// MD5 of LANES messages at once, None if any of them does not fit into a single block
+pub fn compute(messages: &[&[u8]; LANES]) -> Option<[[u8; 16]; LANES]> {
+    if messages
+        .iter()
+        .any(|message| message.len() > MAX_MESSAGE_LENGTH)
+    {
+        return None;
+    }
+
+    let words = load_block(messages);
+    let mut state = INIT.map(|value| [value; LANES]);
+
+    // Four rounds of 16 steps, each with its own mixing function, word order and rotations.
+    // Rotations repeat every 4 steps and are passed as literals, so they stay constant.
+    let f = |b: u32, c: u32, d: u32| (b & c) | (!b & d);
+    for step in (0..16).step_by(4) {
+        mix(&mut state, &words[step], step, 7, f);
+        mix(&mut state, &words[step + 1], step + 1, 12, f);
+        mix(&mut state, &words[step + 2], step + 2, 17, f);
+        mix(&mut state, &words[step + 3], step + 3, 22, f);
+    }
+
+    let g = |b: u32, c: u32, d: u32| (d & b) | (!d & c);
+    for step in (16..32).step_by(4) {
+        mix(&mut state, &words[(5 * step + 1) % 16], step, 5, g);
+        mix(&mut state, &words[(5 * step + 6) % 16], step + 1, 9, g);
+        mix(&mut state, &words[(5 * step + 11) % 16], step + 2, 14, g);
+        mix(&mut state, &words[5 * step % 16], step + 3, 20, g);
+    }
+
+    let h = |b: u32, c: u32, d: u32| b ^ c ^ d;
+    for step in (32..48).step_by(4) {
+        mix(&mut state, &words[(3 * step + 5) % 16], step, 4, h);
+        mix(&mut state, &words[(3 * step + 8) % 16], step + 1, 11, h);
+        mix(&mut state, &words[(3 * step + 11) % 16], step + 2, 16, h);
+        mix(&mut state, &words[(3 * step + 14) % 16], step + 3, 23, h);
+    }
+
+    let i = |b: u32, c: u32, d: u32| c ^ (b | !d);
+    for step in (48..64).step_by(4) {
+        mix(&mut state, &words[7 * step % 16], step, 6, i);
+        mix(&mut state, &words[(7 * step + 7) % 16], step + 1, 10, i);
+        mix(&mut state, &words[(7 * step + 14) % 16], step + 2, 15, i);
+        mix(&mut state, &words[(7 * step + 21) % 16], step + 3, 21, i);
+    }
+
+    Some(std::array::from_fn(|lane| {
+        let mut digest = [0_u8; 16];
+
+        for (index, state) in state.iter().enumerate() {
+            let value = state[lane].wrapping_add(INIT[index]);
+            digest[4 * index..4 * index + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        digest
+    }))
+}
+
+// Padded block as 16 little endian words, lane is the inner index
+fn load_block(messages: &[&[u8]; LANES]) -> [Lanes; 16] {
+    let mut words = [[0_u32; LANES]; 16];
+
+    for (lane, message) in messages.iter().enumerate() {
+        let mut block = [0_u8; 64];
+        block[..message.len()].copy_from_slice(message);
+        block[message.len()] = 0x80;
+        block[56..].copy_from_slice(&(message.len() as u64 * 8).to_le_bytes());
+
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+            word[lane] = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+    }
+
+    words
+}
+
+// One MD5 step on all lanes, state is rotated so that `a` becomes the next `b`
+#[inline(always)]
+fn mix<F>(state: &mut [Lanes; 4], word: &Lanes, step: usize, shift: u32, f: F)
+where
+    F: Fn(u32, u32, u32) -> u32,
+{
+    let [a, b, c, d] = state;
+    let mut next = [0_u32; LANES];
+
+    for lane in 0..LANES {
+        next[lane] = b[lane].wrapping_add(
+            f(b[lane], c[lane], d[lane])
+                .wrapping_add(a[lane])
+                .wrapping_add(CONSTANTS[step])
+                .wrapping_add(word[lane])
+                .rotate_left(shift),
+        );
+    }
+
+    *state = [*d, next, *b, *c];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic pseudo random bytes
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn bytes(&mut self, length: usize) -> Vec<u8> {
+            (0..length).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    #[test]
+    fn test_compute_random() {
+        let mut rng = XorShift(0x2016_0005);
+
+        for _ in 0..1000 {
+            let messages: [Vec<u8>; LANES] = std::array::from_fn(|_| {
+                let length = (rng.next() % (MAX_MESSAGE_LENGTH as u64 + 1)) as usize;
+                rng.bytes(length)
+            });
+            let slices = std::array::from_fn(|lane| messages[lane].as_slice());
+
+            let digests = compute(&slices).unwrap();
+
+            for (message, digest) in messages.iter().zip(digests) {
+                assert_eq!(digest, md5::compute(message).0, "message {message:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_lengths() {
+        // Every length around the padding boundaries
+        let message = [b'x'; MAX_MESSAGE_LENGTH];
+
+        for length in 0..=MAX_MESSAGE_LENGTH {
+            let digests = compute(&[&message[..length]; LANES]).unwrap();
+            assert_eq!(digests[0], md5::compute(&message[..length]).0);
+        }
+    }
+
+    #[test]
+    fn test_compute_too_long() {
+        let long = [0_u8; MAX_MESSAGE_LENGTH + 1];
+        let mut messages: [&[u8]; LANES] = [b"abc"; LANES];
+        messages[LANES - 1] = &long;
+
+        assert_eq!(compute(&messages), None);
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::VecDeque;
+
+use crate::puzzle::hash_algorithm::{HashAlgorithm, LANES, Md5};
+
+const ASCII_DIGIT_0: u8 = 48;
+// u64::MAX has 20 decimal digits
+pub const NUMBER_DIGITS: usize = 20;
+// Salt and number fit into this stack buffer for all but very long salts
+const BUFFER_LENGTH: usize = 64;
+// Numbers hashed by one thread in a single round
+const BLOCK_SIZE: u64 = 100_000;
+
+// Returns n-th hex digit (nibble) of the digest, 0 is the highest nibble of the first byte
+#[inline]
+pub fn nibble(digest: &[u8], n: usize) -> u8 {
+    let byte = digest[n / 2];
+
+    if n.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0x0F
+    }
+}
+
+pub type DigestPredicate = Box<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+pub enum Condition {
+    // Digest starts with given number of zero hex digits
+    Zeros(usize),
+    // Any other check of the digest prefix
+    Predicate(DigestPredicate),
+}
+
+impl Condition {
+    #[inline]
+    pub fn matches(&self, digest: &[u8]) -> bool {
+        match self {
+            // Compare whole bytes first, odd count has to check also the higher nibble
+            Self::Zeros(zeros) => {
+                digest[..zeros / 2].iter().all(|byte| *byte == 0)
+                    && (zeros.is_multiple_of(2) || nibble(digest, zeros - 1) == 0)
+            }
+            Self::Predicate(predicate) => predicate(digest),
+        }
+    }
+}
+
+// Mines hashes of salt + decimal index for digests matching the condition
+pub struct Miner<H: HashAlgorithm = Md5> {
+    salt: String,
+    condition: Condition,
+    algorithm: H,
+    threads: usize,
+}
+
+impl Miner {
+    pub fn new(salt: &str, condition: Condition) -> Self {
+        Self::with_algorithm(salt, condition, Md5)
+    }
+}
+
+impl<H: HashAlgorithm> Miner<H> {
+    pub fn with_algorithm(salt: &str, condition: Condition, algorithm: H) -> Self {
+        // Number of worker threads, falls back to single thread if unknown
+        let threads = std::thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(1);
+
+        Self {
+            salt: salt.to_string(),
+            condition,
+            algorithm,
+            threads,
+        }
+    }
+
+    pub fn get_salt(&self) -> &str {
+        &self.salt
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    // Lazy iterator over all matches starting at index 0, ordered by index
+    pub fn iter(&self) -> Hits<'_, H> {
+        self.iter_from(0)
+    }
+
+    pub fn iter_from(&self, first: u64) -> Hits<'_, H> {
+        Hits {
+            miner: self,
+            next: Some(first),
+            pending: VecDeque::new(),
+        }
+    }
+
+    // Hits of one parallel batch at a time, batches without hits are returned too. Lets callers
+    // notice progress through long stretches without any hit.
+    pub fn batches_from(&self, first: u64) -> impl Iterator<Item = Batch<H::Digest>> + '_ {
+        let mut next = Some(first);
+
+        std::iter::from_fn(move || {
+            let first = next?;
+            next = first.checked_add(self.threads as u64 * BLOCK_SIZE);
+
+            Some(Batch {
+                hits: self.find_hits_parallel(first),
+                next,
+            })
+        })
+    }
+
+    // Matches mapped by `extract`, digests for which it returns None are skipped
+    pub fn values<T, F>(&self, extract: F) -> impl Iterator<Item = (u64, T)>
+    where
+        F: Fn(&H::Digest) -> Option<T>,
+    {
+        self.values_from(0, extract)
+    }
+
+    pub fn values_from<T, F>(&self, first: u64, extract: F) -> impl Iterator<Item = (u64, T)>
+    where
+        F: Fn(&H::Digest) -> Option<T>,
+    {
+        self.iter_from(first)
+            .filter_map(move |(index, digest)| extract(&digest).map(|value| (index, value)))
+    }
+
+    // Writes decimal digits of `number` to the start of `array`, returns number of digits
+    #[inline]
+    pub fn number_to_u8(mut number: u64, array: &mut [u8; NUMBER_DIGITS]) -> usize {
+        let mut index = 0;
+
+        // Emit at least one digit so 0 is converted to "0"
+        loop {
+            let code = (number % 10) as u8;
+            number /= 10;
+
+            array[index] = code + ASCII_DIGIT_0;
+            index += 1;
+
+            if number == 0 {
+                break;
+            }
+        }
+
+        array[..index].reverse();
+        index
+    }
+
+    // Hashes numbers [first, first + count) and returns matching digests. Range is cut at
+    // u64::MAX, numbers never wrap around.
+    fn find_hits(&self, first: u64, count: u64) -> Vec<(u64, H::Digest)> {
+        let last = first.saturating_add(count - 1);
+
+        if self.salt.len() + NUMBER_DIGITS <= BUFFER_LENGTH {
+            let mut buffers = [[0_u8; BUFFER_LENGTH]; LANES];
+            self.find_hits_in(&mut buffers, first, last)
+        } else {
+            // Long salt, buffers are allocated once per block instead of once per hash
+            let mut buffers: [Vec<u8>; LANES] =
+                std::array::from_fn(|_| vec![0_u8; self.salt.len() + NUMBER_DIGITS]);
+            self.find_hits_in(&mut buffers, first, last)
+        }
+    }
+
+    // Every buffer has to hold salt and the longest number. Numbers are hashed LANES at a time,
+    // the tail of the range which does not fill all lanes is hashed one by one.
+    #[inline]
+    fn find_hits_in<B>(
+        &self,
+        buffers: &mut [B; LANES],
+        first: u64,
+        last: u64,
+    ) -> Vec<(u64, H::Digest)>
+    where
+        B: AsRef<[u8]> + AsMut<[u8]>,
+    {
+        let mut hits = Vec::new();
+        let mut lengths = [0_usize; LANES];
+
+        // Buffer for number only
+        let mut buffer_number = [0_u8; NUMBER_DIGITS];
+
+        // Insert salt, it is same for all iterations
+        for buffer in buffers.iter_mut() {
+            buffer.as_mut()[..self.salt.len()].copy_from_slice(self.salt.as_bytes());
+        }
+
+        let mut number = first;
+        loop {
+            // Numbers left in the range including `number`, capped to the lane count
+            let count = (last - number).min(LANES as u64 - 1) as usize + 1;
+
+            // Prepare buffers, salt is already filled
+            // Convert manually number to chars
+            for (lane, buffer) in buffers.iter_mut().enumerate().take(count) {
+                let buffer_number_len =
+                    Self::number_to_u8(number + lane as u64, &mut buffer_number);
+                lengths[lane] = self.salt.len() + buffer_number_len;
+
+                buffer.as_mut()[self.salt.len()..lengths[lane]]
+                    .copy_from_slice(&buffer_number[..buffer_number_len]);
+            }
+
+            // Calculate hashes
+            if count == LANES {
+                let data = std::array::from_fn(|lane| &buffers[lane].as_ref()[..lengths[lane]]);
+
+                for (lane, digest) in self.algorithm.hash_lanes(&data).into_iter().enumerate() {
+                    if self.condition.matches(digest.as_ref()) {
+                        hits.push((number + lane as u64, digest));
+                    }
+                }
+            } else {
+                for lane in 0..count {
+                    let digest = self
+                        .algorithm
+                        .hash(&buffers[lane].as_ref()[..lengths[lane]]);
+
+                    if self.condition.matches(digest.as_ref()) {
+                        hits.push((number + lane as u64, digest));
+                    }
+                }
+            }
+
+            if last - number < LANES as u64 {
+                break;
+            }
+            number += LANES as u64;
+        }
+
+        hits
+    }
+
+    // Every thread hashes one block of BLOCK_SIZE numbers starting at `first`. Blocks are
+    // joined in thread order so hits are always sorted by number, regardless of which
+    // thread finished first.
+    fn find_hits_parallel(&self, first: u64) -> Vec<(u64, H::Digest)> {
+        std::thread::scope(|scope| {
+            // Blocks starting beyond u64::MAX are skipped
+            let handles = (0..self.threads as u64)
+                .filter_map(|thread| first.checked_add(thread * BLOCK_SIZE))
+                .map(|start| scope.spawn(move || self.find_hits(start, BLOCK_SIZE)))
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("Hashing thread panicked"))
+                .collect()
+        })
+    }
+}
+
+pub struct Batch<D> {
+    // Ordered by index
+    pub hits: Vec<(u64, D)>,
+    // First index after the batch, None once the whole u64 range is searched
+    pub next: Option<u64>,
+}
+
+pub struct Hits<'a, H: HashAlgorithm> {
+    miner: &'a Miner<H>,
+    // None once the whole u64 range is searched
+    next: Option<u64>,
+    pending: VecDeque<(u64, H::Digest)>,
+}
+
+impl<H: HashAlgorithm> Iterator for Hits<'_, H> {
+    type Item = (u64, H::Digest);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let first = self.next?;
+
+            self.pending.extend(self.miner.find_hits_parallel(first));
+            self.next = first.checked_add(self.miner.threads as u64 * BLOCK_SIZE);
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::hash_algorithm::StretchedMd5;
+
+    #[test]
+    fn test_nibble() {
+        let digest = [0x12, 0x34];
+
+        assert_eq!(nibble(&digest, 0), 0x1);
+        assert_eq!(nibble(&digest, 1), 0x2);
+        assert_eq!(nibble(&digest, 2), 0x3);
+        assert_eq!(nibble(&digest, 3), 0x4);
+    }
+
+    #[test]
+    fn test_condition_zeros() {
+        assert!(Condition::Zeros(5).matches(&[0x00, 0x00, 0x0F, 0xFF]));
+        assert!(!Condition::Zeros(5).matches(&[0x00, 0x00, 0x10, 0x00]));
+        assert!(Condition::Zeros(4).matches(&[0x00, 0x00, 0xF0, 0x00]));
+        assert!(!Condition::Zeros(4).matches(&[0x00, 0x01, 0x00, 0x00]));
+        assert!(Condition::Zeros(0).matches(&[0xFF]));
+    }
+
+    #[test]
+    fn test_condition_predicate() {
+        let condition = Condition::Predicate(Box::new(|digest| digest[0] == 0xAB));
+
+        assert!(condition.matches(&[0xAB, 0x00]));
+        assert!(!condition.matches(&[0xBA, 0x00]));
+    }
+
+    #[test]
+    fn test_iter() {
+        let miner = Miner::new("abc", Condition::Zeros(5));
+        let hits = miner.iter().take(2).collect::<Vec<_>>();
+
+        assert_eq!(hits[0].0, 3231929);
+        assert_eq!(
+            format!("{:x}", md5::Digest(hits[0].1)),
+            "00000155f8105dff7f56ee10fa9b9abd"
+        );
+        assert_eq!(hits[1].0, 5017308);
+    }
+
+    #[test]
+    fn test_iter_from() {
+        let miner = Miner::new("abc", Condition::Zeros(5));
+
+        assert_eq!(miner.iter_from(3231930).next().unwrap().0, 5017308);
+    }
+
+    #[test]
+    fn test_values() {
+        let miner = Miner::new("abc", Condition::Zeros(5));
+        let values = miner
+            .values(|digest| Some(nibble(digest, 5)))
+            .take(2)
+            .collect::<Vec<_>>();
+
+        assert_eq!(values, vec![(3231929, 1), (5017308, 8)]);
+    }
+
+    #[test]
+    fn test_find_hits_long_salt() {
+        // Salt does not fit into the stack buffer
+        let salt = "x".repeat(100);
+        let miner = Miner::new(&salt, Condition::Zeros(0));
+        let hits = miner.find_hits(99_999_999, 2);
+
+        assert_eq!(hits[0].1, md5::compute(format!("{salt}99999999")).0);
+        assert_eq!(hits[1].1, md5::compute(format!("{salt}100000000")).0);
+    }
+
+    #[test]
+    fn test_find_hits_partial_lanes() {
+        // Range shorter than the lane count and one ending in a partly filled batch
+        let miner = Miner::new("abc", Condition::Zeros(0));
+
+        for count in [1, LANES as u64 - 1, LANES as u64 + 3] {
+            let hits = miner.find_hits(95, count);
+
+            assert_eq!(hits.len(), count as usize);
+            for (index, digest) in hits {
+                assert_eq!(digest, md5::compute(format!("abc{index}")).0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_hits_large_index() {
+        let miner = Miner::new("abc", Condition::Zeros(0));
+        let hits = miner.find_hits(12_345_678_901_234, 1);
+
+        assert_eq!(hits[0].1, md5::compute("abc12345678901234").0);
+    }
+
+    #[test]
+    fn test_iter_ends_at_u64_max() {
+        let miner = Miner::new("abc", Condition::Zeros(0)).with_threads(3);
+        let hits = miner.iter_from(u64::MAX - 2).collect::<Vec<_>>();
+
+        assert_eq!(
+            hits.iter()
+                .map(|(index, _digest)| *index)
+                .collect::<Vec<_>>(),
+            vec![u64::MAX - 2, u64::MAX - 1, u64::MAX]
+        );
+        assert_eq!(hits[2].1, md5::compute(format!("abc{}", u64::MAX)).0);
+    }
+
+    #[test]
+    fn test_batches_from() {
+        let miner = Miner::new("abc", Condition::Zeros(5)).with_threads(2);
+        let batches = miner.batches_from(3_000_000).take(2).collect::<Vec<_>>();
+
+        // First hit is at 3231929, the batch before it has none
+        assert!(batches[0].hits.is_empty());
+        assert_eq!(batches[0].next, Some(3_200_000));
+        assert_eq!(batches[1].hits[0].0, 3231929);
+        assert_eq!(batches[1].next, Some(3_400_000));
+    }
+
+    #[test]
+    fn test_with_algorithm() {
+        let miner = Miner::with_algorithm("abc", Condition::Zeros(0), StretchedMd5::new(1));
+        let hits = miner.find_hits(0, 1);
+
+        assert_eq!(hits[0].1, StretchedMd5::new(1).hash(b"abc0"));
+    }
+
+    #[test]
+    fn test_find_hits_parallel() {
+        let miner = Miner::new("abc", Condition::Zeros(5)).with_threads(4);
+
+        // Same hits in the same order as a single threaded scan
+        assert_eq!(
+            miner.find_hits_parallel(3_000_000),
+            miner.find_hits(3_000_000, 4 * BLOCK_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_number_to_u8() {
+        let mut array = [0_u8; NUMBER_DIGITS];
+
+        assert_eq!(Miner::<Md5>::number_to_u8(123, &mut array), 3);
+        assert_eq!(
+            array[..4],
+            [1 + ASCII_DIGIT_0, 2 + ASCII_DIGIT_0, 3 + ASCII_DIGIT_0, 0]
+        );
+    }
+
+    #[test]
+    fn test_number_to_u8_max() {
+        let mut array = [0_u8; NUMBER_DIGITS];
+
+        assert_eq!(
+            Miner::<Md5>::number_to_u8(u64::MAX, &mut array),
+            NUMBER_DIGITS
+        );
+        assert_eq!(&array, b"18446744073709551615");
+    }
+
+    #[test]
+    fn test_number_to_u8_zero() {
+        let mut array = [0_u8; NUMBER_DIGITS];
+
+        assert_eq!(Miner::<Md5>::number_to_u8(0, &mut array), 1);
+        assert_eq!(array[0], ASCII_DIGIT_0);
+    }
+}
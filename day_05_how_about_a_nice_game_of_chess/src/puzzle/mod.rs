@@ -1,2 +1,10 @@
+pub mod checkpoint;
 pub mod hacker;
+pub mod hash_algorithm;
+pub mod hit_index;
+pub mod live_display;
+#[cfg(feature = "multi_lane")]
+pub mod md5_lanes;
+pub mod miner;
+pub mod password_rule;
 pub mod solution;
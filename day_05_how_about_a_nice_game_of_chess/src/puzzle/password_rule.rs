@@ -0,0 +1,410 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::puzzle::miner::{Condition, nibble};
+
+// _ means no pwd yet in the position
+pub const EMPTY: char = '_';
+const HEX_DIGITS: &[char] = &[
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+];
+
+// Which hit keeps the position if several hits point to it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Winner {
+    First,
+    Last,
+}
+
+// How interesting hashes are turned into a password. Nibbles are counted from 0 like in
+// `nibble`, so the 6th hex digit is nibble 5.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordRule {
+    pub length: usize,
+    // Number of leading zero hex digits of an interesting hash
+    pub zeros: usize,
+    // Nibble with the password position, None fills positions one by one in hit order
+    pub position: Option<usize>,
+    // Nibble with the password character
+    pub value: usize,
+    // Password is finished once every position is filled, so Last only replaces characters
+    // found before that. It makes no difference without a position nibble.
+    pub winner: Winner,
+}
+
+impl PasswordRule {
+    // Part 1, 6th hex digit is the next password character
+    pub fn simple() -> Self {
+        Self {
+            length: 8,
+            zeros: 5,
+            position: None,
+            value: 5,
+            winner: Winner::First,
+        }
+    }
+
+    // Part 2, 6th hex digit is the position and 7th hex digit is the character
+    pub fn advanced() -> Self {
+        Self {
+            position: Some(5),
+            value: 6,
+            ..Self::simple()
+        }
+    }
+
+    // Rule fits digests of `digest_nibbles` hex digits. Fields are public, so the hacker checks
+    // the rule against its algorithm before mining instead of panicking on a short digest.
+    pub fn validate(&self, digest_nibbles: usize) -> Result<(), Box<dyn Error>> {
+        self.validate_fields()?;
+
+        if self.zeros > digest_nibbles {
+            return Err(format!("Zeros must be at most {digest_nibbles}").into());
+        }
+
+        if let Some(nibble) = [Some(self.value), self.position]
+            .into_iter()
+            .flatten()
+            .find(|nibble| *nibble >= digest_nibbles)
+        {
+            return Err(format!(
+                "Nibble {nibble} is outside of the {digest_nibbles} nibble digest"
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    // Checks independent of the digest
+    fn validate_fields(&self) -> Result<(), Box<dyn Error>> {
+        if self.length == 0 {
+            return Err("Password length must be at least 1".into());
+        }
+
+        if self.position.is_some_and(|position| position == self.value) {
+            return Err("Position and value must use different nibbles".into());
+        }
+
+        // Position nibble can only address the first 16 characters
+        if self.position.is_some() && self.length > HEX_DIGITS.len() {
+            return Err(format!(
+                "Password length must be at most {} with a position nibble",
+                HEX_DIGITS.len()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    pub fn condition(&self) -> Condition {
+        Condition::Zeros(self.zeros)
+    }
+
+    // Password from already mined digests ordered by index, None if there are not enough of them.
+    // Digests not matching the zero prefix of the rule are skipped.
+    pub fn password_from_digests<'a, I>(&self, digests: I) -> Option<String>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let condition = self.condition();
+        let mut pwd = Password::new(self);
+
+        for digest in digests
+            .into_iter()
+            .filter(|digest| condition.matches(digest))
+        {
+            pwd.apply(digest);
+
+            if pwd.is_complete() {
+                return Some(pwd.to_string());
+            }
+        }
+
+        None
+    }
+}
+
+impl Display for PasswordRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = match self.position {
+            Some(position) => position.to_string(),
+            None => String::from("none"),
+        };
+        let winner = match self.winner {
+            Winner::First => "first",
+            Winner::Last => "last",
+        };
+
+        write!(
+            f,
+            "length={},zeros={},position={position},value={},winner={winner}",
+            self.length, self.zeros, self.value
+        )
+    }
+}
+
+// Comma separated key=value pairs as written by Display, keys left out keep the simple preset
+impl FromStr for PasswordRule {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rule = Self::simple();
+
+        let to_usize = |value: &str| -> Result<usize, Box<dyn Error>> {
+            value
+                .parse::<usize>()
+                .map_err(|_err| format!("Failed to convert '{value}' to usize").into())
+        };
+
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key=value, found '{pair}'"))?;
+
+            match key {
+                "length" => rule.length = to_usize(value)?,
+                "zeros" => rule.zeros = to_usize(value)?,
+                "position" if value == "none" => rule.position = None,
+                "position" => rule.position = Some(to_usize(value)?),
+                "value" => rule.value = to_usize(value)?,
+                "winner" => {
+                    rule.winner = match value {
+                        "first" => Winner::First,
+                        "last" => Winner::Last,
+                        _ => return Err(format!("Unknown winner '{value}'").into()),
+                    }
+                }
+                _ => return Err(format!("Unknown password rule key '{key}'").into()),
+            }
+        }
+
+        // Digest size depends on the algorithm, it is checked before cracking
+        rule.validate_fields()?;
+        Ok(rule)
+    }
+}
+
+// Password being filled according to a rule
+#[derive(Debug, Clone)]
+pub struct Password<'a> {
+    rule: &'a PasswordRule,
+    pwd: Vec<char>,
+}
+
+impl<'a> Password<'a> {
+    pub fn new(rule: &'a PasswordRule) -> Self {
+        Self {
+            rule,
+            pwd: vec![EMPTY; rule.length],
+        }
+    }
+
+    // Continues a partial password, None if its length does not fit the rule
+    pub fn from_partial(rule: &'a PasswordRule, partial: &str) -> Option<Self> {
+        let pwd = partial.chars().collect::<Vec<_>>();
+        (pwd.len() == rule.length).then_some(Self { rule, pwd })
+    }
+
+    pub fn as_chars(&self) -> &[char] {
+        &self.pwd
+    }
+
+    pub fn found(&self) -> usize {
+        self.pwd.iter().filter(|c| *c != &EMPTY).count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.found() == self.pwd.len()
+    }
+
+    // Updates the password from an interesting digest, returns true if it has changed. Digest
+    // has to be long enough for both nibbles of the rule.
+    pub fn apply(&mut self, digest: &[u8]) -> bool {
+        let code = HEX_DIGITS[nibble(digest, self.rule.value) as usize];
+
+        let position = match self.rule.position {
+            Some(position) => nibble(digest, position) as usize,
+            None => match self.pwd.iter().position(|c| c == &EMPTY) {
+                Some(position) => position,
+                None => return false,
+            },
+        };
+
+        match self.pwd.get_mut(position) {
+            // Invalid positions are ignored
+            None => false,
+            Some(slot) if *slot == EMPTY || self.rule.winner == Winner::Last => {
+                let changed = *slot != code;
+                *slot = code;
+                changed
+            }
+            Some(_slot) => false,
+        }
+    }
+}
+
+impl Display for Password<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pwd.iter().collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_from_digests() {
+        let digests: Vec<&[u8]> = vec![&[0, 0, 0x01, 0x00], &[0, 0, 0x0a, 0x00]];
+        let rule = PasswordRule::simple();
+
+        assert_eq!(rule.password_from_digests(digests.clone()), None);
+        assert_eq!(
+            rule.password_from_digests(digests.into_iter().cycle().take(8)),
+            Some(String::from("1a1a1a1a"))
+        );
+    }
+
+    #[test]
+    fn test_password_advanced_from_digests() {
+        let mut digests: Vec<[u8; 4]> = (0..8).map(|p| [0, 0, p, p * 16]).collect();
+
+        // Later hit for position 0 and invalid position are ignored
+        digests.insert(1, [0, 0, 0x00, 0xf0]);
+        digests.insert(2, [0, 0, 0x09, 0x10]);
+
+        assert_eq!(
+            PasswordRule::advanced().password_from_digests(digests.iter().map(|d| d.as_slice())),
+            Some(String::from("01234567"))
+        );
+    }
+
+    #[test]
+    fn test_winner_last() {
+        let rule = PasswordRule {
+            length: 2,
+            winner: Winner::Last,
+            ..PasswordRule::advanced()
+        };
+        let digests: Vec<&[u8]> = vec![
+            &[0, 0, 0x00, 0x10],
+            &[0, 0, 0x00, 0x20],
+            &[0, 0, 0x01, 0x30],
+            &[0, 0, 0x00, 0x40],
+        ];
+
+        // Second hit replaces the first one, the last one comes after the password is complete
+        assert_eq!(
+            rule.password_from_digests(digests),
+            Some(String::from("23"))
+        );
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        // Four zeros, 5th hex digit is the character, 6th is the position
+        let rule = PasswordRule {
+            length: 2,
+            zeros: 4,
+            position: Some(5),
+            value: 4,
+            winner: Winner::First,
+        };
+        let digests: Vec<&[u8]> = vec![&[0, 0, 0xa1, 0], &[0, 0x10, 0xb0, 0], &[0, 0, 0xc0, 0]];
+
+        // Second digest does not have four zeros
+        assert_eq!(
+            rule.password_from_digests(digests),
+            Some(String::from("ca"))
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let rule = PasswordRule::advanced();
+        let mut pwd = Password::new(&rule);
+
+        assert!(pwd.apply(&[0, 0, 0x01, 0x50]));
+        assert!(!pwd.apply(&[0, 0, 0x01, 0x60]));
+        assert!(!pwd.apply(&[0, 0, 0x08, 0x60]));
+        assert_eq!(pwd.to_string(), "_5______");
+        assert_eq!(pwd.found(), 1);
+    }
+
+    #[test]
+    fn test_from_partial() {
+        let rule = PasswordRule::simple();
+        let mut pwd = Password::from_partial(&rule, "18f_____").unwrap();
+
+        assert!(pwd.apply(&[0, 0, 0x04, 0]));
+        assert_eq!(pwd.to_string(), "18f4____");
+        assert!(Password::from_partial(&rule, "18f").is_none());
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        for rule in [PasswordRule::simple(), PasswordRule::advanced()] {
+            assert_eq!(rule.to_string().parse::<PasswordRule>().unwrap(), rule);
+        }
+
+        assert_eq!(
+            PasswordRule::advanced().to_string(),
+            "length=8,zeros=5,position=5,value=6,winner=first"
+        );
+        assert_eq!(
+            "position=5, value=6".parse::<PasswordRule>().unwrap(),
+            PasswordRule::advanced()
+        );
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!("length=0".parse::<PasswordRule>().is_err());
+        assert!("length=x".parse::<PasswordRule>().is_err());
+        assert!("winner=middle".parse::<PasswordRule>().is_err());
+        assert!("speed=1".parse::<PasswordRule>().is_err());
+        assert!("position".parse::<PasswordRule>().is_err());
+        assert!("position=3,value=3".parse::<PasswordRule>().is_err());
+    }
+
+    #[test]
+    fn test_from_str_position_length() {
+        // Position nibble could never fill the 17th character
+        assert!(
+            "length=17,position=5,value=6"
+                .parse::<PasswordRule>()
+                .is_err()
+        );
+        assert!(
+            "length=16,position=5,value=6"
+                .parse::<PasswordRule>()
+                .is_ok()
+        );
+        assert!("length=17".parse::<PasswordRule>().is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let rule = |s: &str| s.parse::<PasswordRule>().unwrap();
+
+        // Would index past a 16 byte digest
+        assert!(rule("value=40").validate(32).is_err());
+        assert!(rule("value=32").validate(32).is_err());
+        assert!(rule("position=40").validate(32).is_err());
+        assert!(rule("zeros=33").validate(32).is_err());
+        assert!(rule("zeros=32,value=31").validate(32).is_ok());
+
+        // Same nibble fits a 32 byte digest, presets do not fit a 2 byte one
+        assert!(rule("value=40").validate(64).is_ok());
+        assert!(PasswordRule::simple().validate(4).is_err());
+
+        let fields = PasswordRule {
+            length: 0,
+            ..PasswordRule::simple()
+        };
+        assert!(fields.validate(32).is_err());
+    }
+}
@@ -1,39 +1,216 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use puzzler::env::project;
 use puzzler::puzzler::puzzle::Puzzle;
 
 use crate::puzzle::hacker::Hacker;
+use crate::puzzle::hit_index::HitIndex;
+use crate::puzzle::live_display::LiveDisplay;
+use crate::puzzle::password_rule::PasswordRule;
 
-pub struct Solution {}
+pub struct Solution {
+    door_id: String,
+    // Door id given on command line, input file is not read at all
+    door_id_override: bool,
+    live_display: bool,
+    checkpoint: Option<PathBuf>,
+    index_path: Option<PathBuf>,
+    // Hits shared by both parts, mined on first use
+    index: Option<HitIndex>,
+}
 
 impl Puzzle for Solution {
     fn name(&self) -> &str {
         "--- Day 5: How About a Nice Game of Chess? ---"
     }
 
-    fn solve_part1(&mut self) -> Result<String, Box<dyn std::error::Error>> {
-        let pwd = Hacker::new("reyedfim").crack_password();
-        Ok(pwd)
+    fn get_input_file_path(&self) -> Option<PathBuf> {
+        if self.door_id_override {
+            return None;
+        }
+
+        Some(
+            project::get_project_file("../input/day_05.txt")
+                .unwrap_or_else(|err| panic!("Failed to fetch file ../input/day_05.txt [{err}]")),
+        )
     }
 
-    fn solve_part2(&mut self) -> Result<String, Box<dyn std::error::Error>> {
-        let pwd = Hacker::new("reyedfim").crack_password_advanced();
-        Ok(pwd)
+    fn parse_content(&mut self, lines: Vec<String>) -> Result<(), Box<dyn Error>> {
+        if lines.len() != 1 {
+            return Err(format!("Exactly 1 line is expected, found {}", lines.len()).into());
+        }
+
+        self.door_id = Self::parse_door_id(&lines[0])?;
+        Ok(())
+    }
+
+    fn solve_part1(&mut self) -> Result<String, Box<dyn Error>> {
+        if !self.is_cracking_per_part() {
+            return self.get_index()?.password();
+        }
+
+        self.crack("part1", &PasswordRule::simple())
+    }
+
+    fn solve_part2(&mut self) -> Result<String, Box<dyn Error>> {
+        if !self.is_cracking_per_part() {
+            return self.get_index()?.password_advanced();
+        }
+
+        self.crack("part2", &PasswordRule::advanced())
     }
 }
 
 impl Solution {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            door_id: String::new(),
+            door_id_override: false,
+            live_display: false,
+            checkpoint: None,
+            index_path: None,
+            index: None,
+        }
+    }
+
+    // Use door id from command line instead of the input file
+    pub fn with_door_id(mut self, door_id: &str) -> Result<Self, Box<dyn Error>> {
+        self.door_id = Self::parse_door_id(door_id)?;
+        self.door_id_override = true;
+        Ok(self)
+    }
+
+    fn parse_door_id(door_id: &str) -> Result<String, Box<dyn Error>> {
+        let door_id = door_id.trim();
+
+        if door_id.is_empty() {
+            return Err("Door id is empty".into());
+        }
+
+        if let Some(c) = door_id.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(format!("Door id '{door_id}' contains invalid character '{c}'").into());
+        }
+
+        Ok(door_id.to_string())
+    }
+
+    // Animate cracking progress in the terminal
+    pub fn with_live_display(mut self, live_display: bool) -> Self {
+        self.live_display = live_display;
+        self
+    }
+
+    // Each part keeps its own checkpoint file, `path` gets ".part1" or ".part2" appended
+    pub fn with_checkpoint(mut self, path: &Path) -> Self {
+        self.checkpoint = Some(path.to_path_buf());
+        self
+    }
+
+    // Hit index is loaded from `path`, or mined and saved there if the file does not exist yet.
+    // Invalid index or one of another door id fails the solution instead of being overwritten.
+    pub fn with_index(mut self, path: &Path) -> Self {
+        self.index_path = Some(path.to_path_buf());
+        self
+    }
+
+    // Live display and checkpoints follow a single cracking run, they skip the shared index
+    fn is_cracking_per_part(&self) -> bool {
+        self.live_display || self.checkpoint.is_some()
+    }
+
+    fn get_index(&mut self) -> Result<&HitIndex, Box<dyn Error>> {
+        if self.index.is_none() {
+            let loaded = match &self.index_path {
+                Some(path) => HitIndex::load(path)
+                    .map_err(|err| format!("Failed to load index '{}' [{err}]", path.display()))?,
+                None => None,
+            };
+
+            if let Some(index) = &loaded
+                && index.door_id != self.door_id
+            {
+                return Err(format!(
+                    "Index is for door id '{}', expected '{}'",
+                    index.door_id, self.door_id
+                )
+                .into());
+            }
+
+            let index = match loaded {
+                Some(index) => index,
+                None => {
+                    let index = HitIndex::build(&self.door_id);
+
+                    if let Some(path) = &self.index_path {
+                        index.save(path)?;
+                    }
+
+                    index
+                }
+            };
+
+            self.index = Some(index);
+        }
+
+        self.index
+            .as_ref()
+            .ok_or_else(|| "Failed to create hit index".into())
+    }
+
+    fn crack(&self, part: &str, rule: &PasswordRule) -> Result<String, Box<dyn Error>> {
+        let hacker = self.get_hacker(part);
+
+        if self.live_display {
+            // Boxed error can't leave the cracking thread
+            LiveDisplay::run(rule.length, |observer| {
+                hacker
+                    .crack_with(rule, observer)
+                    .map_err(|err| err.to_string())
+            })
+            .map_err(Into::into)
+        } else {
+            hacker.crack(rule)
+        }
+    }
+
+    fn get_hacker(&self, part: &str) -> Hacker {
+        let hacker = Hacker::new(&self.door_id);
+
+        match &self.checkpoint {
+            Some(path) => {
+                let mut path = path.clone().into_os_string();
+                path.push(format!(".{part}"));
+
+                hacker.with_checkpoint(Path::new(&path))
+            }
+            None => hacker,
+        }
+    }
+}
+
+impl Default for Solution {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use puzzler::env::project;
+    use std::fs;
+
     use puzzler::puzzler::puzzle::Puzzle;
 
+    use crate::puzzle::hit_index::HitIndex;
     use crate::puzzle::solution::Solution;
 
     fn get_puzzle() -> Solution {
-        let mut solution = Solution::new();
+        // Precomputed hits, no hashing needed
+        let index = project::get_project_file("../input/day_05_index.json").unwrap_or_else(|err| {
+            panic!("Failed to fetch file ../input/day_05_index.json [{err}]")
+        });
+        let mut solution = Solution::new().with_index(&index);
 
         solution
             .parse_input_file()
@@ -51,4 +228,68 @@ mod tests {
     fn test_solve_part2() {
         assert_eq!(get_puzzle().solve_part2().unwrap(), "863dde27");
     }
+
+    #[test]
+    fn test_with_door_id() {
+        let mut solution = Solution::new().with_door_id("abc").unwrap();
+
+        assert_eq!(solution.get_input_file_path(), None);
+        assert_eq!(solution.solve_part1().unwrap(), "18f47a30");
+    }
+
+    #[test]
+    fn test_index_not_overwritten() {
+        let path =
+            std::env::temp_dir().join(format!("day_05_solution_{}.json", std::process::id()));
+        let mut solution = Solution::new()
+            .with_door_id("abc")
+            .unwrap()
+            .with_index(&path);
+
+        // Corrupt index is reported, not replaced by a fresh one
+        fs::write(&path, "{").unwrap();
+        assert!(solution.solve_part1().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{");
+
+        // Same for a valid index of another door id
+        let other = HitIndex {
+            door_id: String::from("abd"),
+            hits: Vec::new(),
+        };
+        other.save(&path).unwrap();
+        assert!(solution.solve_part1().is_err());
+        assert_eq!(HitIndex::load(&path).unwrap(), Some(other));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_content() {
+        let mut solution = Solution::new();
+
+        assert!(solution.parse_content(vec![String::from("abc")]).is_ok());
+        assert_eq!(solution.door_id, "abc");
+        assert!(solution.parse_content(vec![]).is_err());
+        assert!(
+            solution
+                .parse_content(vec![String::from("abc"), String::from("def")])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_door_id() {
+        assert_eq!(Solution::parse_door_id(" reyedfim ").unwrap(), "reyedfim");
+        assert_eq!(
+            Solution::parse_door_id(&"a".repeat(100)).unwrap(),
+            "a".repeat(100)
+        );
+    }
+
+    #[test]
+    fn test_parse_door_id_invalid() {
+        assert!(Solution::parse_door_id("").is_err());
+        assert!(Solution::parse_door_id("door id").is_err());
+        assert!(Solution::parse_door_id("doör").is_err());
+    }
 }
@@ -1,13 +1,134 @@
 use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
 
+use puzzler::puzzler::puzzle::Puzzle;
 use puzzler::puzzler::solver::Solver;
 
+use crate::puzzle::column_report::MessageReport;
+use crate::puzzle::noise::{NoiseModel, NoiseSimulator};
 use crate::puzzle::solution::Solution;
+use crate::puzzle::strategy::Strategy;
+use crate::puzzle::stream_decoder::StreamDecoder;
+use crate::puzzle::unit::Unit;
 
 mod puzzle;
 
+// Fixed seed keeps simulations reproducible
+const NOISE_SEED: u64 = 2016;
+const NOISE_TRIALS: usize = 100;
+
+fn parse_arg<T: std::str::FromStr>(
+    args: &[&str],
+    index: usize,
+    name: &str,
+) -> Result<T, Box<dyn Error>> {
+    let arg = args.get(index).ok_or_else(|| format!("Missing {name}"))?;
+
+    arg.parse::<T>()
+        .map_err(|_err| format!("Failed to convert '{arg}' to {name}").into())
+}
+
+// Input file as raw bytes, it does not have to be text for byte units
+fn read_signal(solution: &Solution) -> Result<Vec<u8>, Box<dyn Error>> {
+    let path = solution
+        .get_input_file_path()
+        .ok_or("Missing input file path")?;
+
+    fs::read(&path).map_err(|err| format!("Failed to read '{}' [{err}]", path.display()).into())
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let puzzle = Box::new(Solution::new());
-    let mut solver = Solver::new(puzzle, 2);
-    solver.run()
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    // Messages of different length are accepted with --ragged
+    let ragged = args.iter().any(|arg| arg == "--ragged");
+    let args = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--ragged")
+        .collect::<Vec<_>>();
+
+    let mut solution = Solution::new().with_ragged(ragged);
+
+    match args.first() {
+        // Ranked units and confidence of every column, "report [byte|char|grapheme]"
+        Some(&"report") if args.len() > 1 => {
+            let unit = parse_arg::<Unit>(&args, 1, "unit")?;
+
+            print!("{}", unit.report(&read_signal(&solution)?, ragged)?);
+            Ok(())
+        }
+        // Ranked characters and confidence of every column
+        Some(&"report") => {
+            solution.parse_input_file()?;
+
+            print!("{}", MessageReport::new(solution.get_messages()));
+            Ok(())
+        }
+        // Decode input in other units, e.g. "decode majority grapheme", bytes are written raw
+        Some(&"decode") if args.len() > 2 => {
+            let strategy = parse_arg::<Strategy>(&args, 1, "strategy")?;
+            let unit = parse_arg::<Unit>(&args, 2, "unit")?;
+
+            let mut stdout = io::stdout();
+            stdout.write_all(&unit.decode(&read_signal(&solution)?, strategy, ragged)?)?;
+            stdout.write_all(b"\n")?;
+            Ok(())
+        }
+        // Decode input with any strategy, e.g. "decode median"
+        Some(&"decode") => {
+            let strategy = args
+                .get(1)
+                .ok_or("Missing strategy after 'decode'")?
+                .parse::<Strategy>()?;
+
+            solution.parse_input_file()?;
+
+            println!(
+                "{}",
+                Solution::corrected_message(solution.get_messages(), strategy)?
+            );
+            Ok(())
+        }
+        // Noisy repetitions of a secret, "noise <secret> <repeats> <rate> [letters]"
+        Some(&"noise") => {
+            let secret = args.get(1).ok_or("Missing secret")?;
+            let repeats = parse_arg::<usize>(&args, 2, "repeats")?;
+            let model = NoiseModel::new(parse_arg::<f64>(&args, 3, "rate")?, args.get(4).copied());
+            NoiseSimulator::validate(secret, &model)?;
+
+            for line in NoiseSimulator::new(model, NOISE_SEED).generate(secret, repeats) {
+                println!("{line}");
+            }
+            Ok(())
+        }
+        // Recovery of both decoders by noise rate, "simulate <secret> <repeats> [letters]"
+        Some(&"simulate") => {
+            let secret = args.get(1).ok_or("Missing secret")?;
+            let repeats = parse_arg::<usize>(&args, 2, "repeats")?;
+            let model = NoiseModel::new(0.0, args.get(3).copied());
+            NoiseSimulator::validate(secret, &model)?;
+
+            let rates = (0..=10).map(|step| step as f64 / 10.0).collect::<Vec<_>>();
+
+            println!("rate  majority  minority");
+            for row in
+                NoiseSimulator::sweep(&model, NOISE_SEED, secret, repeats, NOISE_TRIALS, &rates)
+            {
+                println!(
+                    "{:.1}   {:>7.2}   {:>7.2}",
+                    row.rate, row.majority, row.minority
+                );
+            }
+            Ok(())
+        }
+        // Decode messages from stdin, best message is printed after every line
+        Some(&"stream") => StreamDecoder::new().run(io::stdin().lock(), &mut io::stdout()),
+        _ => {
+            let puzzle = Box::new(solution);
+            let mut solver = Solver::new(puzzle, 2);
+            solver.run()
+        }
+    }
 }
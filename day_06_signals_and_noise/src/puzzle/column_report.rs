@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::hash::Hash;
+
+// Frequencies of one message column. Units are characters by default, but any ordered unit
+// works (bytes, graphemes). Equal counts are always ranked by unit order (alphabetically for
+// characters), so ties are won by the smaller unit, both for the most and the least common one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnReport<T = char> {
+    // Most common first, equal counts alphabetically
+    pub ranked: Vec<(T, usize)>,
+    pub total: usize,
+}
+
+impl<T: Ord + Hash + Clone> ColumnReport<T> {
+    pub fn new<I>(column: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counts: HashMap<T, usize> = HashMap::new();
+
+        for c in column {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+
+        let mut ranked = counts.into_iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        Self {
+            total: ranked.iter().map(|(_c, count)| count).sum(),
+            ranked,
+        }
+    }
+
+    // Candidates in the order they are picked, ties stay alphabetical
+    fn candidates(&self, least_common: bool) -> Vec<(T, usize)> {
+        let mut candidates = self.ranked.clone();
+
+        if least_common {
+            candidates.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+        }
+
+        candidates
+    }
+
+    // None for an empty column
+    pub fn pick(&self, least_common: bool) -> Option<T> {
+        self.candidates(least_common)
+            .into_iter()
+            .next()
+            .map(|(unit, _count)| unit)
+    }
+
+    // Count difference between the picked character and the runner-up, 0 means a tie. Single
+    // candidate wins by its whole count.
+    pub fn margin(&self, least_common: bool) -> usize {
+        match self.candidates(least_common).as_slice() {
+            [] => 0,
+            [(_c, count)] => *count,
+            [(_first, first), (_second, second), ..] => first.abs_diff(*second),
+        }
+    }
+
+    // Margin relative to the number of messages
+    pub fn confidence(&self, least_common: bool) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        self.margin(least_common) as f64 / self.total as f64
+    }
+
+    // Picked character was decided by the tie rule only
+    pub fn is_ambiguous(&self, least_common: bool) -> bool {
+        self.margin(least_common) == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct MessageReport<T = char> {
+    pub columns: Vec<ColumnReport<T>>,
+}
+
+impl<T: Ord + Hash + Clone> MessageReport<T> {
+    // Shorter messages are counted only in the columns they reach
+    pub fn new(messages: &[Vec<T>]) -> Self {
+        let length = messages
+            .iter()
+            .map(|message| message.len())
+            .max()
+            .unwrap_or(0);
+
+        let columns = (0..length)
+            .map(|column| {
+                ColumnReport::new(
+                    messages
+                        .iter()
+                        .filter_map(|message| message.get(column).cloned()),
+                )
+            })
+            .collect();
+
+        Self { columns }
+    }
+
+    pub fn message(&self, least_common: bool) -> Vec<T> {
+        self.columns
+            .iter()
+            .filter_map(|column| column.pick(least_common))
+            .collect()
+    }
+
+    pub fn ambiguous_columns(&self, least_common: bool) -> Vec<usize> {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter(|(_index, column)| column.is_ambiguous(least_common))
+            .map(|(index, _column)| index)
+            .collect()
+    }
+}
+
+impl<T: Ord + Hash + Clone + Display> Display for MessageReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = |least_common| {
+            self.message(least_common)
+                .iter()
+                .map(|unit| unit.to_string())
+                .collect::<String>()
+        };
+
+        writeln!(f, "Most common:  {}", message(false))?;
+        writeln!(f, "Least common: {}", message(true))?;
+        writeln!(
+            f,
+            "Ambiguous columns: most {:?}, least {:?}",
+            self.ambiguous_columns(false),
+            self.ambiguous_columns(true)
+        )?;
+
+        for (index, column) in self.columns.iter().enumerate() {
+            writeln!(f)?;
+            writeln!(f, "Column {index}:")?;
+
+            for (label, least_common) in [("most ", false), ("least", true)] {
+                let flag = if column.is_ambiguous(least_common) {
+                    "  AMBIGUOUS"
+                } else {
+                    ""
+                };
+
+                writeln!(
+                    f,
+                    "  {label} '{}'  margin {} ({:.1}%){flag}",
+                    column
+                        .pick(least_common)
+                        .map(|unit| unit.to_string())
+                        .unwrap_or_default(),
+                    column.margin(least_common),
+                    column.confidence(least_common) * 100.0
+                )?;
+            }
+
+            let ranked = column
+                .ranked
+                .iter()
+                .map(|(unit, count)| format!("{unit}:{count}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "  ranked {ranked}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranked() {
+        let column = ColumnReport::new("abacbd".chars());
+
+        assert_eq!(column.ranked, vec![('a', 2), ('b', 2), ('c', 1), ('d', 1)]);
+        assert_eq!(column.total, 6);
+    }
+
+    #[test]
+    fn test_ties() {
+        let column = ColumnReport::new("abacbd".chars());
+
+        // Earlier letter wins both ties
+        assert_eq!(column.pick(false), Some('a'));
+        assert_eq!(column.pick(true), Some('c'));
+        assert!(column.is_ambiguous(false));
+        assert!(column.is_ambiguous(true));
+    }
+
+    #[test]
+    fn test_margin() {
+        let column = ColumnReport::new("aaabbc".chars());
+
+        assert_eq!(column.margin(false), 1);
+        assert_eq!(column.margin(true), 1);
+        assert!((column.confidence(false) - 1.0 / 6.0).abs() < 1e-9);
+        assert!(!column.is_ambiguous(false));
+    }
+
+    #[test]
+    fn test_single_candidate() {
+        let column = ColumnReport::new("xxx".chars());
+
+        assert_eq!(column.pick(true), Some('x'));
+        assert_eq!(column.margin(true), 3);
+        assert_eq!(column.confidence(true), 1.0);
+    }
+
+    #[test]
+    fn test_message_report() {
+        let messages = ["ab", "ac", "bd"]
+            .iter()
+            .map(|message| message.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        let report = MessageReport::new(&messages);
+
+        assert_eq!(report.message(false), vec!['a', 'b']);
+        assert_eq!(report.message(true), vec!['b', 'b']);
+        assert_eq!(report.ambiguous_columns(false), vec![1]);
+        assert_eq!(report.ambiguous_columns(true), vec![1]);
+    }
+
+    #[test]
+    fn test_message_report_ragged() {
+        let messages = vec![vec!['a', 'b', 'c'], vec!['a'], vec!['d', 'b']];
+        let report = MessageReport::new(&messages);
+
+        assert_eq!(report.columns[1].total, 2);
+        assert_eq!(report.columns[2].ranked, vec![('c', 1)]);
+        assert_eq!(report.message(false), vec!['a', 'b', 'c']);
+        assert!(MessageReport::<char>::new(&[]).message(false).is_empty());
+    }
+
+    #[test]
+    fn test_bytes() {
+        let messages = vec![vec![0xff, 0x00], vec![0xff, 0x01], vec![0x10, 0x01]];
+        let report = MessageReport::new(&messages);
+
+        assert_eq!(report.message(false), vec![0xff, 0x01]);
+        assert_eq!(report.message(true), vec![0x10, 0x00]);
+    }
+
+    #[test]
+    fn test_display() {
+        let messages = vec![vec!['a'], vec!['a'], vec!['b']];
+        let report = MessageReport::new(&messages).to_string();
+
+        assert!(report.starts_with(
+            "Most common:  a\nLeast common: b\nAmbiguous columns: most [], least []\n"
+        ));
+        assert!(report.contains("  most  'a'  margin 1 (33.3%)\n"));
+        assert!(report.contains("  ranked a:2 b:1\n"));
+        assert!(!report.contains("AMBIGUOUS"));
+    }
+}
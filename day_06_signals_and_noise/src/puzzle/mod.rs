@@ -1 +1,6 @@
+pub mod column_report;
+pub mod noise;
 pub mod solution;
+pub mod strategy;
+pub mod stream_decoder;
+pub mod unit;
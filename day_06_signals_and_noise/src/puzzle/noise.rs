@@ -0,0 +1,285 @@
+use std::error::Error;
+
+use crate::puzzle::strategy::Strategy;
+
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoiseModel {
+    // Every character is replaced with probability `rate` by any other letter
+    Uniform { rate: f64 },
+    // Same as Uniform, but replacements are drawn only from `letters`
+    Biased { rate: f64, letters: Vec<char> },
+}
+
+impl NoiseModel {
+    pub fn rate(&self) -> f64 {
+        match self {
+            Self::Uniform { rate } | Self::Biased { rate, .. } => *rate,
+        }
+    }
+
+    // Biased model if replacement letters are given
+    pub fn new(rate: f64, letters: Option<&str>) -> Self {
+        match letters {
+            Some(letters) => Self::Biased {
+                rate,
+                letters: letters.chars().collect(),
+            },
+            None => Self::Uniform { rate },
+        }
+    }
+
+    pub fn with_rate(&self, rate: f64) -> Self {
+        match self {
+            Self::Uniform { .. } => Self::Uniform { rate },
+            Self::Biased { letters, .. } => Self::Biased {
+                rate,
+                letters: letters.clone(),
+            },
+        }
+    }
+}
+
+// Recovery rates of both puzzle decoders for one noise rate
+#[derive(Debug, PartialEq)]
+pub struct Recovery {
+    pub rate: f64,
+    pub majority: f64,
+    pub minority: f64,
+}
+
+// Generates noisy repetitions of a secret message. Same seed always gives the same lines.
+pub struct NoiseSimulator {
+    model: NoiseModel,
+    // Xorshift state, never 0
+    state: u64,
+}
+
+impl NoiseSimulator {
+    pub fn new(model: NoiseModel, seed: u64) -> Self {
+        Self {
+            model,
+            state: seed.max(1),
+        }
+    }
+
+    // Secret and replacement letters have to be lowercase letters
+    pub fn validate(secret: &str, model: &NoiseModel) -> Result<(), Box<dyn Error>> {
+        if secret.is_empty() {
+            return Err("Secret message is empty".into());
+        }
+
+        if let Some(c) = secret.chars().find(|c| !c.is_ascii_lowercase()) {
+            return Err(format!("Secret contains invalid character '{c}'").into());
+        }
+
+        if !(0.0..=1.0).contains(&model.rate()) {
+            return Err(format!("Noise rate {} is not in [0, 1]", model.rate()).into());
+        }
+
+        if let NoiseModel::Biased { letters, .. } = model {
+            if letters.is_empty() {
+                return Err("Biased noise needs at least one letter".into());
+            }
+
+            if let Some(c) = letters.iter().find(|c| !c.is_ascii_lowercase()) {
+                return Err(format!("Invalid noise letter '{c}'").into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    // Character is kept if there is no other letter to replace it with
+    fn substitute(&mut self, c: char) -> char {
+        let candidates = match &self.model {
+            NoiseModel::Uniform { .. } => LETTERS.chars().filter(|l| *l != c).collect::<Vec<_>>(),
+            NoiseModel::Biased { letters, .. } => {
+                letters.iter().copied().filter(|l| *l != c).collect()
+            }
+        };
+
+        if candidates.is_empty() {
+            return c;
+        }
+
+        candidates[(self.next() % candidates.len() as u64) as usize]
+    }
+
+    pub fn generate(&mut self, secret: &str, repeats: usize) -> Vec<String> {
+        let rate = self.model.rate();
+
+        (0..repeats)
+            .map(|_| {
+                secret
+                    .chars()
+                    .map(|c| {
+                        if self.next_f64() < rate {
+                            self.substitute(c)
+                        } else {
+                            c
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Fraction of `trials` where the strategy decodes exactly the secret
+    pub fn recovery_rate(
+        &mut self,
+        secret: &str,
+        repeats: usize,
+        trials: usize,
+        strategy: Strategy,
+    ) -> f64 {
+        if trials == 0 {
+            return 0.0;
+        }
+
+        let recovered = (0..trials)
+            .filter(|_| {
+                let messages = self
+                    .generate(secret, repeats)
+                    .iter()
+                    .map(|line| line.chars().collect())
+                    .collect::<Vec<Vec<char>>>();
+
+                strategy.decode(&messages) == secret
+            })
+            .count();
+
+        recovered as f64 / trials as f64
+    }
+
+    // Recovery of majority and least common decoding for every rate, rate of `model` is ignored
+    pub fn sweep(
+        model: &NoiseModel,
+        seed: u64,
+        secret: &str,
+        repeats: usize,
+        trials: usize,
+        rates: &[f64],
+    ) -> Vec<Recovery> {
+        rates
+            .iter()
+            .map(|rate| {
+                let mut simulator = Self::new(model.with_rate(*rate), seed);
+
+                Recovery {
+                    rate: *rate,
+                    majority: simulator.recovery_rate(secret, repeats, trials, Strategy::Majority),
+                    minority: simulator.recovery_rate(secret, repeats, trials, Strategy::Minority),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_no_noise() {
+        let lines = NoiseSimulator::new(NoiseModel::Uniform { rate: 0.0 }, 1).generate("abc", 3);
+
+        assert_eq!(lines, vec!["abc", "abc", "abc"]);
+    }
+
+    #[test]
+    fn test_generate_full_noise() {
+        let lines = NoiseSimulator::new(NoiseModel::Uniform { rate: 1.0 }, 7).generate("abc", 50);
+
+        for line in lines {
+            assert_eq!(line.len(), 3);
+            assert!(line.chars().zip("abc".chars()).all(|(a, b)| a != b));
+        }
+    }
+
+    #[test]
+    fn test_generate_biased() {
+        let model = NoiseModel::Biased {
+            rate: 1.0,
+            letters: vec!['x', 'y'],
+        };
+        let lines = NoiseSimulator::new(model, 3).generate("axy", 20);
+
+        // 'x' can only become 'y' and the other way round
+        for line in lines {
+            let chars = line.chars().collect::<Vec<_>>();
+
+            assert!(chars[0] == 'x' || chars[0] == 'y');
+            assert_eq!(chars[1], 'y');
+            assert_eq!(chars[2], 'x');
+        }
+    }
+
+    #[test]
+    fn test_generate_deterministic() {
+        let model = NoiseModel::Uniform { rate: 0.5 };
+
+        assert_eq!(
+            NoiseSimulator::new(model.clone(), 42).generate("secret", 10),
+            NoiseSimulator::new(model, 42).generate("secret", 10)
+        );
+    }
+
+    #[test]
+    fn test_recovery_rate() {
+        let mut simulator = NoiseSimulator::new(NoiseModel::Uniform { rate: 0.0 }, 1);
+
+        assert_eq!(
+            simulator.recovery_rate("secret", 5, 10, Strategy::Majority),
+            1.0
+        );
+        assert_eq!(
+            simulator.recovery_rate("secret", 5, 10, Strategy::Minority),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_sweep() {
+        let model = NoiseModel::Uniform { rate: 0.0 };
+        let rows = NoiseSimulator::sweep(&model, 1, "secret", 100, 5, &[0.1, 1.0]);
+
+        // Light noise is easily outvoted, noise replacing everything hides the secret
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].majority, 1.0);
+        assert_eq!(rows[1].majority, 0.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let uniform = NoiseModel::Uniform { rate: 0.5 };
+
+        assert!(NoiseSimulator::validate("abc", &uniform).is_ok());
+        assert!(NoiseSimulator::validate("", &uniform).is_err());
+        assert!(NoiseSimulator::validate("aBc", &uniform).is_err());
+        assert!(NoiseSimulator::validate("abc", &uniform.with_rate(1.5)).is_err());
+        assert!(
+            NoiseSimulator::validate(
+                "abc",
+                &NoiseModel::Biased {
+                    rate: 0.5,
+                    letters: vec![]
+                }
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,12 +1,16 @@
-use std::collections::HashMap;
 use std::error::Error;
 use std::path::PathBuf;
 
 use puzzler::env::project;
 use puzzler::puzzler::puzzle::Puzzle;
 
+use crate::puzzle::strategy::Strategy;
+use crate::puzzle::unit::Unit;
+
 pub struct Solution {
     messages: Vec<Vec<char>>,
+    // Allow messages of different length, columns count only characters present
+    ragged: bool,
 }
 
 impl Puzzle for Solution {
@@ -22,21 +26,29 @@ impl Puzzle for Solution {
     }
 
     fn parse_content(&mut self, lines: Vec<String>) -> Result<(), Box<dyn Error>> {
-        self.messages = lines
+        let messages = lines
             .iter()
             .map(|line| line.chars().collect::<Vec<char>>())
-            .collect();
+            .collect::<Vec<_>>();
+
+        if messages.is_empty() {
+            return Err("No messages found".into());
+        }
+
+        if !self.ragged {
+            Unit::Char.check_lengths(&messages)?;
+        }
+
+        self.messages = messages;
         Ok(())
     }
 
     fn solve_part1(&mut self) -> Result<String, Box<dyn Error>> {
-        let message = Self::corrected_message(&self.messages, false);
-        Ok(message.to_string())
+        Self::corrected_message(&self.messages, Strategy::Majority)
     }
 
     fn solve_part2(&mut self) -> Result<String, Box<dyn Error>> {
-        let message = Self::corrected_message(&self.messages, true);
-        Ok(message.to_string())
+        Self::corrected_message(&self.messages, Strategy::Minority)
     }
 }
 
@@ -44,37 +56,29 @@ impl Solution {
     pub fn new() -> Self {
         Self {
             messages: Vec::new(),
+            ragged: false,
         }
     }
 
-    fn corrected_message(messages: &[Vec<char>], modified_repetition_code: bool) -> String {
-        let mut message = String::with_capacity(messages.len());
-
-        // For every column count occurences
-        for c in 0..messages[0].len() {
-            let mut counts: HashMap<char, usize> = HashMap::new();
-
-            for message in messages {
-                *counts.entry(message[c]).or_insert(0) += 1;
-            }
+    pub fn with_ragged(mut self, ragged: bool) -> Self {
+        self.ragged = ragged;
+        self
+    }
 
-            // Find max occurence
-            let (pwd, _count) = if modified_repetition_code {
-                counts
-                    .iter()
-                    .min_by(|a, b| a.1.cmp(b.1))
-                    .expect("Failed to find min")
-            } else {
-                counts
-                    .iter()
-                    .max_by(|a, b| a.1.cmp(b.1))
-                    .expect("Failed to find max")
-            };
+    pub fn get_messages(&self) -> &[Vec<char>] {
+        &self.messages
+    }
 
-            message.push(*pwd);
+    // Ties follow the rule of the strategy, the earlier letter wins
+    pub fn corrected_message(
+        messages: &[Vec<char>],
+        strategy: Strategy,
+    ) -> Result<String, Box<dyn Error>> {
+        if messages.is_empty() {
+            return Err("No messages to decode".into());
         }
 
-        message
+        Ok(strategy.decode(messages))
     }
 }
 
@@ -83,6 +87,8 @@ mod tests {
     use puzzler::puzzler::puzzle::Puzzle;
 
     use crate::puzzle::solution::Solution;
+    use crate::puzzle::strategy::Strategy;
+    use crate::puzzle::stream_decoder::StreamDecoder;
 
     fn get_puzzle() -> Solution {
         let mut solution = Solution::new();
@@ -128,12 +134,72 @@ mod tests {
     #[test]
     fn test_corrected_message() {
         let messages = get_messages();
-        assert_eq!(&Solution::corrected_message(&messages, false), "easter");
+        assert_eq!(
+            &Solution::corrected_message(&messages, Strategy::Majority).unwrap(),
+            "easter"
+        );
     }
 
     #[test]
     fn test_corrected_message_modified_repetition_code() {
         let messages = get_messages();
-        assert_eq!(&Solution::corrected_message(&messages, true), "advent");
+        assert_eq!(
+            &Solution::corrected_message(&messages, Strategy::Minority).unwrap(),
+            "advent"
+        );
+    }
+
+    #[test]
+    fn test_stream_decoder() {
+        // Streaming gives the same answers as the whole input
+        let solution = get_puzzle();
+        let mut decoder = StreamDecoder::new();
+
+        for message in solution.get_messages() {
+            decoder.push(&message.iter().collect::<String>()).unwrap();
+        }
+
+        assert_eq!(decoder.message(false), "kqsdmzft");
+        assert_eq!(decoder.message(true), "tpooccyo");
+    }
+
+    #[test]
+    fn test_corrected_message_empty() {
+        assert!(Solution::corrected_message(&[], Strategy::Majority).is_err());
+    }
+
+    #[test]
+    fn test_parse_content_empty() {
+        assert!(Solution::new().parse_content(vec![]).is_err());
+        assert!(Solution::new().solve_part1().is_err());
+    }
+
+    #[test]
+    fn test_parse_content_ragged() {
+        let lines = vec![String::from("abc"), String::from("ab"), String::from("abd")];
+
+        assert!(Solution::new().parse_content(lines.clone()).is_err());
+
+        let mut solution = Solution::new().with_ragged(true);
+        solution.parse_content(lines).unwrap();
+
+        // Third column only has 'c' and 'd'
+        assert_eq!(solution.solve_part1().unwrap(), "abc");
+        assert_eq!(solution.solve_part2().unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_corrected_message_ties() {
+        // Every column is a tie, result must not depend on hashing order
+        let messages = vec![vec!['b', 'y'], vec!['a', 'z']];
+
+        assert_eq!(
+            &Solution::corrected_message(&messages, Strategy::Majority).unwrap(),
+            "ay"
+        );
+        assert_eq!(
+            &Solution::corrected_message(&messages, Strategy::Minority).unwrap(),
+            "ay"
+        );
     }
 }
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::hash::Hash;
+use std::str::FromStr;
+
+use crate::puzzle::column_report::MessageReport;
+
+// How the repeated messages are combined into one. Ties are always won by the earlier letter,
+// or by the earlier message for HammingConsensus.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    // Most common character of every column (part 1)
+    Majority,
+    // Least common character of every column (part 2)
+    Minority,
+    // Majority where the n-th message counts n + 1 times, later repetitions are trusted more
+    PositionWeighted,
+    // Lower median of every column, characters are ordered by their code point
+    Median,
+    // Input message with the smallest total Hamming distance to all other messages
+    HammingConsensus,
+}
+
+impl Strategy {
+    pub fn decode(&self, messages: &[Vec<char>]) -> String {
+        self.decode_units(messages).into_iter().collect()
+    }
+
+    // Same as decode for any ordered unit, "alphabetically" means in the order of the unit
+    pub fn decode_units<T: Ord + Hash + Clone>(&self, messages: &[Vec<T>]) -> Vec<T> {
+        match self {
+            Self::Majority => MessageReport::new(messages).message(false),
+            Self::Minority => MessageReport::new(messages).message(true),
+            Self::PositionWeighted => Self::position_weighted(messages),
+            Self::Median => Self::median(messages),
+            Self::HammingConsensus => Self::hamming_consensus(messages),
+        }
+    }
+
+    // Longest message, shorter ones are skipped in the columns they do not reach
+    fn columns<T>(messages: &[Vec<T>]) -> usize {
+        messages
+            .iter()
+            .map(|message| message.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn position_weighted<T: Ord + Hash + Clone>(messages: &[Vec<T>]) -> Vec<T> {
+        (0..Self::columns(messages))
+            .filter_map(|column| {
+                let mut weights: HashMap<&T, usize> = HashMap::new();
+
+                for (position, message) in messages.iter().enumerate() {
+                    if let Some(unit) = message.get(column) {
+                        *weights.entry(unit).or_insert(0) += position + 1;
+                    }
+                }
+
+                weights
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+                    .map(|(unit, _weight)| unit.clone())
+            })
+            .collect()
+    }
+
+    fn median<T: Ord + Clone>(messages: &[Vec<T>]) -> Vec<T> {
+        (0..Self::columns(messages))
+            .filter_map(|column| {
+                let mut column = messages
+                    .iter()
+                    .filter_map(|message| message.get(column))
+                    .collect::<Vec<_>>();
+                column.sort_unstable();
+
+                // Lower median for even number of messages
+                column
+                    .get(column.len().saturating_sub(1) / 2)
+                    .map(|unit| (*unit).clone())
+            })
+            .collect()
+    }
+
+    fn hamming_consensus<T: PartialEq + Clone>(messages: &[Vec<T>]) -> Vec<T> {
+        // Missing characters of a shorter message count as differences
+        let distance = |a: &[T], b: &[T]| {
+            a.iter().zip(b).filter(|(a, b)| a != b).count() + a.len().abs_diff(b.len())
+        };
+
+        messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| {
+                let total = messages
+                    .iter()
+                    .map(|other| distance(message, other))
+                    .sum::<usize>();
+                (total, index)
+            })
+            .min()
+            .map(|(_total, index)| messages[index].clone())
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "majority" => Ok(Self::Majority),
+            "minority" => Ok(Self::Minority),
+            "weighted" => Ok(Self::PositionWeighted),
+            "median" => Ok(Self::Median),
+            "hamming" => Ok(Self::HammingConsensus),
+            _ => Err(format!("Unknown strategy '{s}'").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_messages(messages: &[&str]) -> Vec<Vec<char>> {
+        messages
+            .iter()
+            .map(|message| message.chars().collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_majority_minority() {
+        let messages = to_messages(&["ab", "ab", "cd"]);
+
+        assert_eq!(Strategy::Majority.decode(&messages), "ab");
+        assert_eq!(Strategy::Minority.decode(&messages), "cd");
+    }
+
+    #[test]
+    fn test_position_weighted() {
+        // Weights 1 + 2 = 3 for "ab" and 3 + 4 = 7 for "cd"
+        let messages = to_messages(&["ab", "ab", "cd", "cd"]);
+
+        assert_eq!(Strategy::PositionWeighted.decode(&messages), "cd");
+        assert_eq!(
+            Strategy::PositionWeighted.decode(&to_messages(&["ab", "ab", "ab", "cd"])),
+            "ab"
+        );
+    }
+
+    #[test]
+    fn test_position_weighted_ties() {
+        // Weights 1 + 2 = 3 for 'b' and 3 for 'a'
+        let messages = to_messages(&["b", "b", "a"]);
+
+        assert_eq!(Strategy::PositionWeighted.decode(&messages), "a");
+    }
+
+    #[test]
+    fn test_median() {
+        let messages = to_messages(&["az", "by", "cx"]);
+        assert_eq!(Strategy::Median.decode(&messages), "by");
+
+        // Lower median for even count
+        let messages = to_messages(&["a", "b", "c", "d"]);
+        assert_eq!(Strategy::Median.decode(&messages), "b");
+    }
+
+    #[test]
+    fn test_hamming_consensus() {
+        let messages = to_messages(&["abcd", "abce", "xbcd", "abcd"]);
+
+        assert_eq!(Strategy::HammingConsensus.decode(&messages), "abcd");
+    }
+
+    #[test]
+    fn test_hamming_consensus_ties() {
+        let messages = to_messages(&["ab", "cd"]);
+
+        assert_eq!(Strategy::HammingConsensus.decode(&messages), "ab");
+    }
+
+    #[test]
+    fn test_ragged() {
+        let messages = to_messages(&["abc", "ab", "xbd"]);
+
+        assert_eq!(Strategy::PositionWeighted.decode(&messages), "abd");
+        assert_eq!(Strategy::Median.decode(&messages), "abc");
+        assert_eq!(Strategy::HammingConsensus.decode(&messages), "abc");
+    }
+
+    #[test]
+    fn test_decode_units() {
+        let messages = vec![vec![3_u8, 200], vec![3, 100], vec![7, 100]];
+
+        assert_eq!(Strategy::Majority.decode_units(&messages), vec![3, 100]);
+        assert_eq!(Strategy::Median.decode_units(&messages), vec![3, 100]);
+        assert_eq!(
+            Strategy::HammingConsensus.decode_units(&messages),
+            vec![3, 100]
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Strategy::Median.decode(&[]), "");
+        assert_eq!(Strategy::HammingConsensus.decode(&[]), "");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "weighted".parse::<Strategy>().unwrap(),
+            Strategy::PositionWeighted
+        );
+        assert_eq!(
+            "hamming".parse::<Strategy>().unwrap(),
+            Strategy::HammingConsensus
+        );
+        assert!("mode".parse::<Strategy>().is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+// Signal uses lowercase letters only
+const ALPHABET: usize = 26;
+
+// Per-column letter counts updated one message at a time, memory does not grow with the number
+// of messages. Ties are won by the earlier letter, same as in ColumnReport.
+#[derive(Debug, Default)]
+pub struct StreamDecoder {
+    counts: Vec<[usize; ALPHABET]>,
+    lines: usize,
+}
+
+impl StreamDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_lines(&self) -> usize {
+        self.lines
+    }
+
+    // First message sets the number of columns, later ones have to match it
+    pub fn push(&mut self, message: &str) -> Result<(), Box<dyn Error>> {
+        let length = message.chars().count();
+
+        if self.lines == 0 {
+            self.counts = vec![[0; ALPHABET]; length];
+        } else if length != self.counts.len() {
+            return Err(format!(
+                "Message {} has {length} characters, expected {}",
+                self.lines + 1,
+                self.counts.len()
+            )
+            .into());
+        }
+
+        // Validate whole message first so a bad one leaves counts untouched
+        if let Some(c) = message.chars().find(|c| !c.is_ascii_lowercase()) {
+            return Err(format!("Invalid character '{c}' in message {}", self.lines + 1).into());
+        }
+
+        for (counts, letter) in self.counts.iter_mut().zip(message.bytes()) {
+            counts[(letter - b'a') as usize] += 1;
+        }
+
+        self.lines += 1;
+        Ok(())
+    }
+
+    // Best message so far, least common picks only from letters seen in the column
+    pub fn message(&self, least_common: bool) -> String {
+        self.counts
+            .iter()
+            .filter_map(|counts| {
+                let seen = counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_letter, count)| **count > 0);
+
+                // max_by keeps the last of equal elements, reversed letter order lets the earlier
+                // letter win
+                let best = if least_common {
+                    seen.min_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(&b.0)))
+                } else {
+                    seen.max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+                };
+
+                best.map(|(letter, _count)| (b'a' + letter as u8) as char)
+            })
+            .collect()
+    }
+
+    // Reads messages until the end of input and writes the most and least common message
+    // after every one of them. Invalid messages are reported and skipped, only I/O errors end
+    // the feed.
+    pub fn run<R, W>(&mut self, input: R, output: &mut W) -> Result<(), Box<dyn Error>>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        for (index, line) in input.lines().enumerate() {
+            if let Err(err) = self.push(line?.trim()) {
+                writeln!(output, "line {} skipped [{err}]", index + 1)?;
+                continue;
+            }
+
+            writeln!(
+                output,
+                "{} {} {}",
+                self.get_lines(),
+                self.message(false),
+                self.message(true)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGES: &str = "eedadn\ndrvtee\neandsr\nraavrd\natevrs\ntsrnev\nsdttsa\nrasrtv\n\
+                            nssdts\nntnada\nsvetve\ntesnvt\nvntsnd\nvrdear\ndvrsen\nenarar\n";
+
+    #[test]
+    fn test_message() {
+        let mut decoder = StreamDecoder::new();
+
+        for message in MESSAGES.lines() {
+            decoder.push(message).unwrap();
+        }
+
+        assert_eq!(decoder.get_lines(), 16);
+        assert_eq!(decoder.message(false), "easter");
+        assert_eq!(decoder.message(true), "advent");
+    }
+
+    #[test]
+    fn test_ties() {
+        let mut decoder = StreamDecoder::new();
+        decoder.push("by").unwrap();
+        decoder.push("az").unwrap();
+
+        assert_eq!(decoder.message(false), "ay");
+        assert_eq!(decoder.message(true), "ay");
+    }
+
+    #[test]
+    fn test_push_invalid() {
+        let mut decoder = StreamDecoder::new();
+        decoder.push("abc").unwrap();
+
+        assert!(decoder.push("ab").is_err());
+        assert!(decoder.push("aBc").is_err());
+        assert_eq!(decoder.get_lines(), 1);
+        assert_eq!(decoder.message(false), "abc");
+    }
+
+    #[test]
+    fn test_run() {
+        let mut output = Vec::new();
+        StreamDecoder::new()
+            .run("ab\nab\ncd\n".as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1 ab ab\n2 ab ab\n3 ab cd\n"
+        );
+    }
+
+    #[test]
+    fn test_run_skips_invalid() {
+        let mut output = Vec::new();
+        StreamDecoder::new()
+            .run("ab\nabc\naB\ncd\n".as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1 ab ab\n\
+             line 2 skipped [Message 2 has 3 characters, expected 2]\n\
+             line 3 skipped [Invalid character 'B' in message 2]\n\
+             2 ab ab\n"
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(StreamDecoder::new().message(false), "");
+    }
+}
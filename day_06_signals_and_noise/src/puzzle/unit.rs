@@ -0,0 +1,284 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::puzzle::column_report::MessageReport;
+use crate::puzzle::strategy::Strategy;
+
+// What a single column position of a message is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    // Raw bytes, the signal does not have to be text
+    Byte,
+    // Unicode scalar values, same as the puzzle solution
+    Char,
+    // Extended grapheme clusters, e.g. 'e' with a combining accent is one unit
+    Grapheme,
+}
+
+// Byte shown as two hex digits in reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexByte(pub u8);
+
+impl fmt::Display for HexByte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+
+impl Unit {
+    // Plural name used in error messages
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Byte => "bytes",
+            Self::Char => "characters",
+            Self::Grapheme => "graphemes",
+        }
+    }
+
+    // All messages have to be as long as the first one
+    pub fn check_lengths<T>(&self, messages: &[Vec<T>]) -> Result<(), Box<dyn Error>> {
+        let Some(first) = messages.first() else {
+            return Err("No messages found".into());
+        };
+        let length = first.len();
+
+        if let Some((index, message)) = messages
+            .iter()
+            .enumerate()
+            .find(|(_index, message)| message.len() != length)
+        {
+            return Err(format!(
+                "Message {} has {} {}, expected {length}",
+                index + 1,
+                message.len(),
+                self.name()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    // Messages are separated by '\n', a trailing newline does not start another message. Same
+    // as str::lines, a '\r' in front of the '\n' is dropped too.
+    fn split_lines(signal: &[u8]) -> Vec<&[u8]> {
+        let signal = signal.strip_suffix(b"\n").unwrap_or(signal);
+
+        if signal.is_empty() {
+            return Vec::new();
+        }
+
+        signal
+            .split(|byte| *byte == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .collect()
+    }
+
+    fn to_text(signal: &[u8]) -> Result<&str, Box<dyn Error>> {
+        std::str::from_utf8(signal)
+            .map_err(|err| format!("Signal is not valid UTF-8 [{err}]").into())
+    }
+
+    fn messages<L, T, F>(
+        &self,
+        lines: impl Iterator<Item = L>,
+        ragged: bool,
+        split: F,
+    ) -> Result<Vec<Vec<T>>, Box<dyn Error>>
+    where
+        F: Fn(L) -> Vec<T>,
+    {
+        let messages = lines.map(split).collect::<Vec<_>>();
+
+        if messages.is_empty() {
+            return Err("No messages found".into());
+        }
+
+        if !ragged {
+            self.check_lengths(&messages)?;
+        }
+
+        Ok(messages)
+    }
+
+    fn byte_messages(
+        &self,
+        signal: &[u8],
+        ragged: bool,
+    ) -> Result<Vec<Vec<HexByte>>, Box<dyn Error>> {
+        self.messages(Self::split_lines(signal).into_iter(), ragged, |line| {
+            line.iter().map(|byte| HexByte(*byte)).collect()
+        })
+    }
+
+    // Decoded message in the encoding of the signal, raw bytes for Byte and UTF-8 otherwise
+    pub fn decode(
+        &self,
+        signal: &[u8],
+        strategy: Strategy,
+        ragged: bool,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        match self {
+            Self::Byte => Ok(strategy
+                .decode_units(&self.byte_messages(signal, ragged)?)
+                .into_iter()
+                .map(|byte| byte.0)
+                .collect()),
+            Self::Char => {
+                let messages = self.messages(Self::to_text(signal)?.lines(), ragged, |line| {
+                    line.chars().collect()
+                })?;
+
+                Ok(strategy.decode(&messages).into_bytes())
+            }
+            Self::Grapheme => {
+                let messages = self.messages(Self::to_text(signal)?.lines(), ragged, |line| {
+                    line.graphemes(true).collect()
+                })?;
+
+                Ok(strategy.decode_units(&messages).concat().into_bytes())
+            }
+        }
+    }
+
+    // Column report of the signal, bytes are shown in hex
+    pub fn report(&self, signal: &[u8], ragged: bool) -> Result<String, Box<dyn Error>> {
+        match self {
+            Self::Byte => Ok(MessageReport::new(&self.byte_messages(signal, ragged)?).to_string()),
+            Self::Char => {
+                let messages = self.messages(Self::to_text(signal)?.lines(), ragged, |line| {
+                    line.chars().collect()
+                })?;
+
+                Ok(MessageReport::new(&messages).to_string())
+            }
+            Self::Grapheme => {
+                let messages = self.messages(Self::to_text(signal)?.lines(), ragged, |line| {
+                    line.graphemes(true).collect()
+                })?;
+
+                Ok(MessageReport::new(&messages).to_string())
+            }
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "byte" => Ok(Self::Byte),
+            "char" => Ok(Self::Char),
+            "grapheme" => Ok(Self::Grapheme),
+            _ => Err(format!("Unknown unit '{s}'").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_matches_solution() {
+        let signal = b"eedadn\ndrvtee\neandsr\nraavrd\natevrs\ntsrnev\nsdttsa\nrasrtv\n\
+                       nssdts\nntnada\nsvetve\ntesnvt\nvntsnd\nvrdear\ndvrsen\nenarar\n";
+
+        assert_eq!(
+            Unit::Char
+                .decode(signal, Strategy::Majority, false)
+                .unwrap(),
+            b"easter"
+        );
+        assert_eq!(
+            Unit::Char
+                .decode(signal, Strategy::Minority, false)
+                .unwrap(),
+            b"advent"
+        );
+    }
+
+    #[test]
+    fn test_grapheme() {
+        // "e\u{301}" is one grapheme but two characters
+        let signal = "e\u{301}x\ne\u{301}y\nay\n".as_bytes();
+
+        assert_eq!(
+            Unit::Grapheme
+                .decode(signal, Strategy::Majority, false)
+                .unwrap(),
+            "e\u{301}y".as_bytes()
+        );
+        assert!(
+            Unit::Char
+                .decode(signal, Strategy::Majority, false)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_byte() {
+        let signal = [0xff, 0x00, b'\n', 0xff, 0x01, b'\n', 0x10, 0x01];
+
+        assert_eq!(
+            Unit::Byte
+                .decode(&signal, Strategy::Majority, false)
+                .unwrap(),
+            vec![0xff, 0x01]
+        );
+        assert!(
+            Unit::Char
+                .decode(&signal, Strategy::Majority, false)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_crlf() {
+        let signal = b"eab\r\nedc\r\nfdb\r\n";
+
+        for unit in [Unit::Byte, Unit::Char, Unit::Grapheme] {
+            assert_eq!(
+                unit.decode(signal, Strategy::Majority, false).unwrap(),
+                b"edb",
+                "{}",
+                unit.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_lengths() {
+        let signal = b"abc\nab\n";
+
+        assert_eq!(
+            Unit::Char
+                .decode(signal, Strategy::Majority, false)
+                .unwrap_err()
+                .to_string(),
+            "Message 2 has 2 characters, expected 3"
+        );
+        assert_eq!(
+            Unit::Byte.decode(signal, Strategy::Majority, true).unwrap(),
+            b"abc"
+        );
+        assert!(Unit::Byte.decode(b"", Strategy::Majority, false).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Unit::Byte.report(&[0xab, b'\n', 0xab], false).unwrap();
+
+        assert!(report.starts_with("Most common:  ab\n"));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("grapheme".parse::<Unit>().unwrap(), Unit::Grapheme);
+        assert!("word".parse::<Unit>().is_err());
+    }
+}
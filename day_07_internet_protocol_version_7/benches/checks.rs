@@ -0,0 +1,57 @@
+// TLS/SSL checks over all addresses of the puzzle input. Throughput is reported in addresses per
+// second.
+//
+// Compare two versions with criterion baselines:
+//   cargo bench -p day_07_internet_protocol_version_7 -- --save-baseline before
+//   (apply changes)
+//   cargo bench -p day_07_internet_protocol_version_7 -- --baseline before
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use day_07_internet_protocol_version_7::puzzle::scanner;
+use day_07_internet_protocol_version_7::puzzle::solution::Solution;
+use puzzler::puzzler::puzzle::Puzzle;
+
+fn bench_checks(c: &mut Criterion) {
+    let mut solution = Solution::new();
+    solution
+        .parse_input_file()
+        .unwrap_or_else(|err| panic!("Failed to parse input file [{err}]"));
+    let addresses = solution.get_addresses();
+
+    for (name, rules, scan) in [
+        (
+            "tls",
+            Solution::supports_tls as fn(&str) -> bool,
+            (|address| scanner::check(address).tls) as fn(&str) -> bool,
+        ),
+        ("ssl", Solution::supports_ssl, |address| {
+            scanner::check(address).ssl
+        }),
+    ] {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Elements(addresses.len() as u64));
+
+        group.bench_function("rules", |b| {
+            b.iter(|| {
+                addresses
+                    .iter()
+                    .filter(|address| rules(black_box(address)))
+                    .count()
+            })
+        });
+        group.bench_function("scanner", |b| {
+            b.iter(|| {
+                addresses
+                    .iter()
+                    .filter(|address| scan(black_box(address)))
+                    .count()
+            })
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_checks);
+criterion_main!(benches);
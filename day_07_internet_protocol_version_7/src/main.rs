@@ -1,13 +1,83 @@
 use std::error::Error;
 
+use day_07_internet_protocol_version_7::puzzle::explanation::Explanation;
+use day_07_internet_protocol_version_7::puzzle::ipv7::Scope;
+use day_07_internet_protocol_version_7::puzzle::rule::Rule;
+use day_07_internet_protocol_version_7::puzzle::solution::Solution;
+use puzzler::puzzler::puzzle::Puzzle;
 use puzzler::puzzler::solver::Solver;
 
-use crate::puzzle::solution::Solution;
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut nested = false;
+    let mut depth = None;
+    let mut args = Vec::new();
+    let mut all_args = std::env::args().skip(1);
 
-mod puzzle;
+    while let Some(arg) = all_args.next() {
+        match arg.as_str() {
+            // Hypernet sequences inside hypernet sequences are accepted
+            "--nested" => nested = true,
+            // Only this depth of nested hypernet sequences counts as hypernet for TLS and SSL
+            "--depth" => {
+                let value = all_args.next().ok_or("Missing depth after '--depth'")?;
+                let value = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|depth| *depth > 0)
+                    .ok_or_else(|| format!("Depth must be a number >= 1, found '{value}'"))?;
+                depth = Some(value);
+            }
+            _ => args.push(arg),
+        }
+    }
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let puzzle = Box::new(Solution::new());
-    let mut solver = Solver::new(puzzle, 2);
-    solver.run()
+    let get_solution = || {
+        let solution = Solution::new().with_nested(nested);
+
+        match depth {
+            Some(depth) => solution.with_rules(
+                Rule::tls_at(Scope::Depth(depth)),
+                Rule::ssl_at(Scope::Depth(depth)),
+            ),
+            None => solution,
+        }
+    };
+
+    match args.first() {
+        // Why every address does or does not support TLS and SSL, "explain [failing]"
+        Some(&"explain") => {
+            let failing_only = match args.get(1) {
+                None => false,
+                Some(&"failing") => true,
+                Some(arg) => return Err(format!("Unknown explain filter '{arg}'").into()),
+            };
+
+            // Explanations always treat every depth >= 1 as hypernet
+            if depth.is_some() {
+                return Err("'--depth' is not supported by explain".into());
+            }
+
+            let mut solution = get_solution();
+            solution.parse_input_file()?;
+
+            for address in solution.get_addresses() {
+                let explanation = if nested {
+                    Explanation::new_nested(address)?
+                } else {
+                    Explanation::new(address)?
+                };
+
+                if !failing_only || explanation.is_failing() {
+                    print!("{explanation}");
+                }
+            }
+            Ok(())
+        }
+        _ => {
+            let puzzle = Box::new(get_solution());
+            let mut solver = Solver::new(puzzle, 2);
+            solver.run()
+        }
+    }
 }
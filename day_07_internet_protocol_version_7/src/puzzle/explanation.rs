@@ -0,0 +1,275 @@
+use std::fmt;
+
+use crate::puzzle::ipv7::{IpV7, IpV7Error, Segment};
+use crate::puzzle::pattern::Pattern;
+
+// Pattern found in an address, offset is relative to the whole address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Found<'a> {
+    pub text: &'a str,
+    pub offset: usize,
+}
+
+impl<'a> Found<'a> {
+    fn all(segment: &Segment<'a>, pattern: &Pattern) -> Vec<Self> {
+        pattern
+            .find(segment.text)
+            .map(|index| Self {
+                text: &segment.text[index..index + pattern.len()],
+                offset: segment.offset + index,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Found<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' at {}", self.text, self.offset)
+    }
+}
+
+// TLS needs an ABBA in a supernet and none in any hypernet
+#[derive(Debug, PartialEq)]
+pub struct TlsExplanation<'a> {
+    pub supernet_abbas: Vec<Found<'a>>,
+    pub hypernet_abbas: Vec<Found<'a>>,
+}
+
+impl<'a> TlsExplanation<'a> {
+    pub fn new(ipv7: &IpV7<'a>) -> Self {
+        let abba = Pattern::template("ABBA");
+
+        Self {
+            supernet_abbas: ipv7
+                .outside()
+                .flat_map(|segment| Found::all(segment, &abba))
+                .collect(),
+            hypernet_abbas: ipv7
+                .inside()
+                .flat_map(|segment| Found::all(segment, &abba))
+                .collect(),
+        }
+    }
+
+    pub fn supported(&self) -> bool {
+        self.hypernet_abbas.is_empty() && !self.supernet_abbas.is_empty()
+    }
+}
+
+impl fmt::Display for TlsExplanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Hypernet ABBA wins over any supernet one, so it is the reason to report first
+        if let Some(abba) = self.hypernet_abbas.first() {
+            write!(f, "TLS no: ABBA {abba} in hypernet")
+        } else if let Some(abba) = self.supernet_abbas.first() {
+            write!(f, "TLS yes: ABBA {abba} in supernet")
+        } else {
+            write!(f, "TLS no: no ABBA in supernet")
+        }
+    }
+}
+
+// SSL needs an ABA in a supernet with the matching BAB in a hypernet
+#[derive(Debug, PartialEq)]
+pub struct SslExplanation<'a> {
+    pub supernet_abas: Vec<Found<'a>>,
+    pub hypernet_babs: Vec<Found<'a>>,
+    // ABA and its matching BAB
+    pub pairs: Vec<(Found<'a>, Found<'a>)>,
+}
+
+impl<'a> SslExplanation<'a> {
+    pub fn new(ipv7: &IpV7<'a>) -> Self {
+        let aba = Pattern::template("ABA");
+
+        let supernet_abas = ipv7
+            .outside()
+            .flat_map(|segment| Found::all(segment, &aba))
+            .collect::<Vec<_>>();
+        let hypernet_babs = ipv7
+            .inside()
+            .flat_map(|segment| Found::all(segment, &aba))
+            .collect::<Vec<_>>();
+
+        let pairs = supernet_abas
+            .iter()
+            .flat_map(|aba| {
+                let aba_bytes = aba.text.as_bytes();
+
+                hypernet_babs
+                    .iter()
+                    .filter(move |bab| {
+                        let bab_bytes = bab.text.as_bytes();
+                        aba_bytes[0] == bab_bytes[1] && aba_bytes[1] == bab_bytes[0]
+                    })
+                    .map(move |bab| (*aba, *bab))
+            })
+            .collect();
+
+        Self {
+            supernet_abas,
+            hypernet_babs,
+            pairs,
+        }
+    }
+
+    pub fn supported(&self) -> bool {
+        !self.pairs.is_empty()
+    }
+}
+
+impl fmt::Display for SslExplanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((aba, bab)) = self.pairs.first() {
+            write!(f, "SSL yes: ABA {aba} matches BAB {bab}")
+        } else if self.supernet_abas.is_empty() {
+            write!(f, "SSL no: no ABA in supernet")
+        } else if self.hypernet_babs.is_empty() {
+            write!(f, "SSL no: no BAB in hypernet")
+        } else {
+            write!(f, "SSL no: no hypernet BAB matches a supernet ABA")
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Explanation<'a> {
+    pub address: &'a str,
+    pub tls: TlsExplanation<'a>,
+    pub ssl: SslExplanation<'a>,
+}
+
+impl<'a> Explanation<'a> {
+    pub fn new(address: &'a str) -> Result<Self, IpV7Error> {
+        Ok(Self::from_ipv7(address, &IpV7::parse(address)?))
+    }
+
+    // Every depth >= 1 counts as hypernet
+    pub fn new_nested(address: &'a str) -> Result<Self, IpV7Error> {
+        Ok(Self::from_ipv7(address, &IpV7::parse_nested(address)?))
+    }
+
+    fn from_ipv7(address: &'a str, ipv7: &IpV7<'a>) -> Self {
+        Self {
+            address,
+            tls: TlsExplanation::new(ipv7),
+            ssl: SslExplanation::new(ipv7),
+        }
+    }
+
+    // Address lacks TLS or SSL support
+    pub fn is_failing(&self) -> bool {
+        !self.tls.supported() || !self.ssl.supported()
+    }
+}
+
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.address)?;
+        writeln!(f, "  {}", self.tls)?;
+        writeln!(f, "  {}", self.ssl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls_supported() {
+        let explanation = Explanation::new("ioxxoj[asdfgh]zxcvbn").unwrap();
+
+        assert!(explanation.tls.supported());
+        assert_eq!(
+            explanation.tls.supernet_abbas,
+            vec![Found {
+                text: "oxxo",
+                offset: 1
+            }]
+        );
+        assert_eq!(
+            explanation.tls.to_string(),
+            "TLS yes: ABBA 'oxxo' at 1 in supernet"
+        );
+    }
+
+    #[test]
+    fn test_tls_not_supported() {
+        assert_eq!(
+            Explanation::new("abcd[bddb]xyyx").unwrap().tls.to_string(),
+            "TLS no: ABBA 'bddb' at 5 in hypernet"
+        );
+        assert_eq!(
+            Explanation::new("aaaa[qwer]tyui").unwrap().tls.to_string(),
+            "TLS no: no ABBA in supernet"
+        );
+    }
+
+    #[test]
+    fn test_ssl_supported() {
+        let explanation = Explanation::new("zazbz[bzb]cdb").unwrap();
+
+        assert!(explanation.ssl.supported());
+        assert_eq!(
+            explanation.ssl.pairs,
+            vec![(
+                Found {
+                    text: "zbz",
+                    offset: 2
+                },
+                Found {
+                    text: "bzb",
+                    offset: 6
+                }
+            )]
+        );
+        assert_eq!(
+            explanation.ssl.to_string(),
+            "SSL yes: ABA 'zbz' at 2 matches BAB 'bzb' at 6"
+        );
+    }
+
+    #[test]
+    fn test_ssl_not_supported() {
+        assert_eq!(
+            Explanation::new("xyx[xyx]xyx").unwrap().ssl.to_string(),
+            "SSL no: no hypernet BAB matches a supernet ABA"
+        );
+        assert_eq!(
+            Explanation::new("aaaa[qwer]tyui").unwrap().ssl.to_string(),
+            "SSL no: no ABA in supernet"
+        );
+        assert_eq!(
+            Explanation::new("aba[qwer]tyui").unwrap().ssl.to_string(),
+            "SSL no: no BAB in hypernet"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let explanation = Explanation::new("abba[mnop]qrst").unwrap();
+
+        assert!(explanation.is_failing());
+        assert_eq!(
+            explanation.to_string(),
+            "abba[mnop]qrst\n  TLS yes: ABBA 'abba' at 0 in supernet\n  SSL no: no ABA in supernet\n"
+        );
+    }
+
+    #[test]
+    fn test_nested() {
+        assert!(Explanation::new("abba[xy[oxxo]z]").is_err());
+        assert_eq!(
+            Explanation::new_nested("abba[xy[oxxo]z]")
+                .unwrap()
+                .tls
+                .to_string(),
+            "TLS no: ABBA 'oxxo' at 8 in hypernet"
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Explanation::new("ab[cd").is_err());
+    }
+}
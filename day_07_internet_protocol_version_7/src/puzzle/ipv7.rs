@@ -1,39 +1,164 @@
-use std::sync::LazyLock;
+use std::error::Error;
+use std::fmt;
 
-use regex::Regex;
+// Which sequences of an address to look at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    // Outside square brackets, depth 0
+    Supernet,
+    // Inside square brackets at any depth
+    Hypernet,
+    // Exactly this depth, e.g. 2 for "c" in "a[b[c]]"
+    Depth(usize),
+}
+
+impl Scope {
+    pub fn contains(&self, depth: usize) -> bool {
+        match self {
+            Self::Supernet => depth == 0,
+            Self::Hypernet => depth > 0,
+            Self::Depth(scope) => depth == *scope,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment<'a> {
+    // Number of enclosing brackets, 0 for supernet sequences
+    pub depth: usize,
+    // Without the brackets
+    pub text: &'a str,
+    // Byte offset of the first character of text within the address
+    pub offset: usize,
+}
+
+// Offsets point to the offending character within the address
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpV7Error {
+    Empty,
+    InvalidCharacter { character: char, offset: usize },
+    NestedBracket { offset: usize },
+    UnclosedBracket { offset: usize },
+    UnexpectedClosingBracket { offset: usize },
+    EmptyHypernet { offset: usize },
+}
 
-// Compile regex only once for all IpV7 instances
-static REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"([^\[\]]+)|\[(.*?)\]").expect("Invalid regex"));
+impl fmt::Display for IpV7Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Address is empty"),
+            Self::InvalidCharacter { character, offset } => {
+                write!(f, "Invalid character '{character}' at {offset}")
+            }
+            Self::NestedBracket { offset } => write!(f, "Nested '[' at {offset}"),
+            Self::UnclosedBracket { offset } => write!(f, "Unclosed '[' at {offset}"),
+            Self::UnexpectedClosingBracket { offset } => {
+                write!(f, "Unexpected ']' at {offset}")
+            }
+            Self::EmptyHypernet { offset } => write!(f, "Empty hypernet sequence at {offset}"),
+        }
+    }
+}
 
+impl Error for IpV7Error {}
+
+// Address split into supernet and hypernet sequences in the order they appear
 #[derive(Debug, PartialEq)]
 pub struct IpV7<'a> {
-    pub outside: Vec<&'a str>,
-    pub inside: Vec<&'a str>,
+    pub segments: Vec<Segment<'a>>,
 }
 
 impl<'a> IpV7<'a> {
-    pub fn new(address: &'a str) -> Self {
-        let (outside, inside) = Self::extract(address);
+    // Lowercase letters with non nested, non empty hypernet sequences in square brackets.
+    // Empty supernet sequences (e.g. "[abc]def") are allowed and skipped.
+    pub fn parse(address: &'a str) -> Result<Self, IpV7Error> {
+        Self::parse_with(address, false)
+    }
 
-        Self { outside, inside }
+    // Same as parse, but hypernet sequences may contain other ones, e.g. "a[b[c]d]e"
+    pub fn parse_nested(address: &'a str) -> Result<Self, IpV7Error> {
+        Self::parse_with(address, true)
     }
 
-    fn extract(address: &'a str) -> (Vec<&'a str>, Vec<&'a str>) {
-        let mut outside = Vec::new();
-        let mut inside = Vec::new();
+    fn parse_with(address: &'a str, nested: bool) -> Result<Self, IpV7Error> {
+        if address.is_empty() {
+            return Err(IpV7Error::Empty);
+        }
 
-        for capture in REGEX.captures_iter(address) {
-            if let Some(out) = capture.get(1) {
-                outside.push(out.as_str());
-            }
+        let mut segments = Vec::new();
+        let mut start = 0;
+        // Offsets of the '[' of all open hypernet sequences, innermost last
+        let mut open: Vec<usize> = Vec::new();
 
-            if let Some(ins) = capture.get(2) {
-                inside.push(ins.as_str());
+        for (offset, character) in address.char_indices() {
+            match character {
+                '[' => {
+                    if !nested && !open.is_empty() {
+                        return Err(IpV7Error::NestedBracket { offset });
+                    }
+
+                    Self::push(&mut segments, open.len(), address, start, offset);
+                    open.push(offset);
+                    start = offset + 1;
+                }
+                ']' => {
+                    let depth = open.len();
+                    let Some(bracket) = open.pop() else {
+                        return Err(IpV7Error::UnexpectedClosingBracket { offset });
+                    };
+
+                    if bracket + 1 == offset {
+                        return Err(IpV7Error::EmptyHypernet { offset: bracket });
+                    }
+
+                    Self::push(&mut segments, depth, address, start, offset);
+                    start = offset + 1;
+                }
+                character if !character.is_ascii_lowercase() => {
+                    return Err(IpV7Error::InvalidCharacter { character, offset });
+                }
+                _ => {}
             }
         }
 
-        (outside, inside)
+        // Outermost bracket is the one left open first
+        if let Some(offset) = open.first() {
+            return Err(IpV7Error::UnclosedBracket { offset: *offset });
+        }
+
+        Self::push(&mut segments, 0, address, start, address.len());
+
+        Ok(Self { segments })
+    }
+
+    fn push(
+        segments: &mut Vec<Segment<'a>>,
+        depth: usize,
+        address: &'a str,
+        start: usize,
+        end: usize,
+    ) {
+        if start < end {
+            segments.push(Segment {
+                depth,
+                text: &address[start..end],
+                offset: start,
+            });
+        }
+    }
+
+    pub fn within(&self, scope: Scope) -> impl Iterator<Item = &Segment<'a>> {
+        self.segments
+            .iter()
+            .filter(move |segment| scope.contains(segment.depth))
+    }
+
+    pub fn outside(&self) -> impl Iterator<Item = &Segment<'a>> {
+        self.within(Scope::Supernet)
+    }
+
+    pub fn inside(&self) -> impl Iterator<Item = &Segment<'a>> {
+        self.within(Scope::Hypernet)
     }
 }
 
@@ -41,25 +166,141 @@ impl<'a> IpV7<'a> {
 mod tests {
     use super::*;
 
+    fn texts<'a>(segments: impl Iterator<Item = &'a Segment<'a>>) -> Vec<&'a str> {
+        segments.map(|segment| segment.text).collect()
+    }
+
     #[test]
-    fn test_new() {
-        assert_eq!(
-            IpV7::new(
-                "abpxdcnbqeoeiidhpt[zpwzuygklghkvrzsogw]mdmjoojzrwdqcywsxd[jbxptisjyvgicpqnw]aanbeosfyeptpuzmrz[pasvleayajolpwhj]hsbidwxbtlfdmsahbu"
-            ),
-            IpV7 {
-                outside: vec![
-                    "abpxdcnbqeoeiidhpt",
-                    "mdmjoojzrwdqcywsxd",
-                    "aanbeosfyeptpuzmrz",
-                    "hsbidwxbtlfdmsahbu"
-                ],
-                inside: vec![
-                    "zpwzuygklghkvrzsogw",
-                    "jbxptisjyvgicpqnw",
-                    "pasvleayajolpwhj"
-                ]
-            }
+    fn test_parse() {
+        let ipv7 = IpV7::parse(
+            "abpxdcnbqeoeiidhpt[zpwzuygklghkvrzsogw]mdmjoojzrwdqcywsxd[jbxptisjyvgicpqnw]aanbeosfyeptpuzmrz[pasvleayajolpwhj]hsbidwxbtlfdmsahbu",
         )
+        .unwrap();
+
+        assert_eq!(
+            texts(ipv7.outside()),
+            vec![
+                "abpxdcnbqeoeiidhpt",
+                "mdmjoojzrwdqcywsxd",
+                "aanbeosfyeptpuzmrz",
+                "hsbidwxbtlfdmsahbu"
+            ]
+        );
+        assert_eq!(
+            texts(ipv7.inside()),
+            vec![
+                "zpwzuygklghkvrzsogw",
+                "jbxptisjyvgicpqnw",
+                "pasvleayajolpwhj"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_offsets() {
+        let ipv7 = IpV7::parse("ab[cd]ef").unwrap();
+
+        assert_eq!(
+            ipv7.segments,
+            vec![
+                Segment {
+                    depth: 0,
+                    text: "ab",
+                    offset: 0
+                },
+                Segment {
+                    depth: 1,
+                    text: "cd",
+                    offset: 3
+                },
+                Segment {
+                    depth: 0,
+                    text: "ef",
+                    offset: 6
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_supernet() {
+        let ipv7 = IpV7::parse("[ab][cd]").unwrap();
+
+        assert_eq!(texts(ipv7.outside()), Vec::<&str>::new());
+        assert_eq!(texts(ipv7.inside()), vec!["ab", "cd"]);
+    }
+
+    #[test]
+    fn test_parse_nested() {
+        let ipv7 = IpV7::parse_nested("a[b[cc]d]e").unwrap();
+
+        assert_eq!(
+            ipv7.segments
+                .iter()
+                .map(|segment| (segment.text, segment.depth, segment.offset))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a", 0, 0),
+                ("b", 1, 2),
+                ("cc", 2, 4),
+                ("d", 1, 7),
+                ("e", 0, 9)
+            ]
+        );
+        assert_eq!(texts(ipv7.inside()), vec!["b", "cc", "d"]);
+        assert_eq!(texts(ipv7.within(Scope::Depth(2))), vec!["cc"]);
+    }
+
+    #[test]
+    fn test_parse_nested_errors() {
+        assert_eq!(
+            IpV7::parse_nested("a[b[c]"),
+            Err(IpV7Error::UnclosedBracket { offset: 1 })
+        );
+        assert_eq!(
+            IpV7::parse_nested("a[b]]"),
+            Err(IpV7Error::UnexpectedClosingBracket { offset: 4 })
+        );
+        assert_eq!(
+            IpV7::parse_nested("a[b[]]"),
+            Err(IpV7Error::EmptyHypernet { offset: 3 })
+        );
+        assert!(IpV7::parse_nested("a[[b]]").is_ok());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(IpV7::parse(""), Err(IpV7Error::Empty));
+        assert_eq!(
+            IpV7::parse("ab[c[d]e]f"),
+            Err(IpV7Error::NestedBracket { offset: 4 })
+        );
+        assert_eq!(
+            IpV7::parse("ab[cd"),
+            Err(IpV7Error::UnclosedBracket { offset: 2 })
+        );
+        assert_eq!(
+            IpV7::parse("ab]cd"),
+            Err(IpV7Error::UnexpectedClosingBracket { offset: 2 })
+        );
+        assert_eq!(
+            IpV7::parse("ab[]cd"),
+            Err(IpV7Error::EmptyHypernet { offset: 2 })
+        );
+        assert_eq!(
+            IpV7::parse("aB[cd]"),
+            Err(IpV7Error::InvalidCharacter {
+                character: 'B',
+                offset: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            IpV7Error::UnclosedBracket { offset: 2 }.to_string(),
+            "Unclosed '[' at 2"
+        );
     }
 }
@@ -1,2 +1,6 @@
+pub mod explanation;
 pub mod ipv7;
+pub mod pattern;
+pub mod rule;
+pub mod scanner;
 pub mod solution;
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
+
+// Palindrome template like "ABBA", "ABA" or "ABCBA". Positions with the same template letter
+// must hold the same byte, positions with different letters must hold different bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    template: String,
+    // Index of the template letter at every position, e.g. [0, 1, 1, 0] for "ABBA"
+    slots: Vec<usize>,
+}
+
+impl Pattern {
+    // Built-in template known to be valid, panics otherwise
+    pub fn template(template: &str) -> Self {
+        template
+            .parse()
+            .unwrap_or_else(|err| panic!("Invalid template '{template}' [{err}]"))
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    // Always false, templates are never empty
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.template.chars()
+    }
+
+    pub fn matches(&self, window: &[u8]) -> bool {
+        if window.len() != self.len() {
+            return false;
+        }
+
+        self.slots.iter().enumerate().all(|(i, slot_i)| {
+            self.slots[i + 1..]
+                .iter()
+                .zip(&window[i + 1..])
+                .all(|(slot_j, byte_j)| (slot_i == slot_j) == (window[i] == *byte_j))
+        })
+    }
+
+    // Start indices of all, possibly overlapping, matches
+    pub fn find<'a>(&'a self, text: &'a str) -> impl Iterator<Item = usize> + 'a {
+        text.as_bytes()
+            .windows(self.len())
+            .enumerate()
+            .filter(|(_index, window)| self.matches(window))
+            .map(|(index, _window)| index)
+    }
+
+    // Byte bound to every template letter by a match
+    pub fn bind(&self, window: &[u8]) -> HashMap<char, u8> {
+        self.letters().zip(window.iter().copied()).collect()
+    }
+
+    // Bytes of this pattern with its letters replaced by the bound ones, None if a letter is
+    // not bound
+    pub fn instantiate(&self, bindings: &HashMap<char, u8>) -> Option<Vec<u8>> {
+        self.letters()
+            .map(|letter| bindings.get(&letter).copied())
+            .collect()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Pattern template is empty".into());
+        }
+
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_uppercase()) {
+            return Err(format!("Invalid template letter '{c}' in '{s}'").into());
+        }
+
+        if !s.chars().eq(s.chars().rev()) {
+            return Err(format!("Template '{s}' is not a palindrome").into());
+        }
+
+        let mut letters: Vec<char> = Vec::new();
+        let slots = s
+            .chars()
+            .map(|c| match letters.iter().position(|letter| *letter == c) {
+                Some(slot) => slot,
+                None => {
+                    letters.push(c);
+                    letters.len() - 1
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            template: s.to_string(),
+            slots,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(template: &str) -> Pattern {
+        Pattern::template(template)
+    }
+
+    #[test]
+    fn test_abba() {
+        let abba = pattern("ABBA");
+
+        assert_eq!(abba.find("abba").collect::<Vec<_>>(), vec![0]);
+        assert_eq!(abba.find("oxyyxo").collect::<Vec<_>>(), vec![1]);
+        assert_eq!(abba.find("abca").count(), 0);
+        assert_eq!(abba.find("abbc").count(), 0);
+        assert_eq!(abba.find("aaaa").count(), 0);
+    }
+
+    #[test]
+    fn test_aba() {
+        let aba = pattern("ABA");
+
+        assert_eq!(aba.find("aba").collect::<Vec<_>>(), vec![0]);
+        assert_eq!(aba.find("abcaba").collect::<Vec<_>>(), vec![3]);
+        assert_eq!(aba.find("zazbz").collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(aba.find("abb").count(), 0);
+        assert_eq!(aba.find("aaa").count(), 0);
+        assert_eq!(aba.find("ab").count(), 0);
+    }
+
+    #[test]
+    fn test_abcba() {
+        let abcba = pattern("ABCBA");
+
+        assert!(abcba.matches(b"xyzyx"));
+        assert!(!abcba.matches(b"xyxyx"));
+        assert!(!abcba.matches(b"xyzyw"));
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let bindings = pattern("ABA").bind(b"xyx");
+
+        assert_eq!(pattern("BAB").instantiate(&bindings), Some(b"yxy".to_vec()));
+        assert_eq!(pattern("CAC").instantiate(&bindings), None);
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!("".parse::<Pattern>().is_err());
+        assert!("abba".parse::<Pattern>().is_err());
+        assert!("ABCA".parse::<Pattern>().is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::puzzle::ipv7::{IpV7, Scope};
+use crate::puzzle::pattern::Pattern;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    // Some sequence in scope contains the pattern
+    Contains(Scope, Pattern),
+    // No sequence in scope contains the pattern
+    Excludes(Scope, Pattern),
+    // Some match in scope has its counterpart in a sequence of the counterpart scope.
+    // Counterpart uses the bytes bound to the letters of the match, e.g. "ABA" outside with
+    // "BAB" inside.
+    Paired {
+        scope: Scope,
+        pattern: Pattern,
+        counterpart_scope: Scope,
+        counterpart: Pattern,
+    },
+}
+
+impl Condition {
+    fn found(ipv7: &IpV7, scope: Scope, pattern: &Pattern) -> bool {
+        ipv7.within(scope)
+            .any(|segment| pattern.find(segment.text).next().is_some())
+    }
+
+    fn holds(&self, ipv7: &IpV7) -> bool {
+        match self {
+            Self::Contains(scope, pattern) => Self::found(ipv7, *scope, pattern),
+            Self::Excludes(scope, pattern) => !Self::found(ipv7, *scope, pattern),
+            Self::Paired {
+                scope,
+                pattern,
+                counterpart_scope,
+                counterpart,
+            } => ipv7.within(*scope).any(|segment| {
+                pattern.find(segment.text).any(|index| {
+                    let window = &segment.text.as_bytes()[index..index + pattern.len()];
+
+                    counterpart
+                        .instantiate(&pattern.bind(window))
+                        .is_some_and(|bytes| {
+                            ipv7.within(*counterpart_scope).any(|other| {
+                                other
+                                    .text
+                                    .as_bytes()
+                                    .windows(bytes.len())
+                                    .any(|other| other == bytes)
+                            })
+                        })
+                })
+            }),
+        }
+    }
+}
+
+// Protocol supported by an address when all of its conditions hold. By default every depth >= 1
+// counts as hypernet, the `_at` variants restrict a condition to any other scope, e.g. a single
+// depth of nested hypernet sequences.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Rule {
+    pub conditions: Vec<Condition>,
+}
+
+impl Rule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ABBA outside and none inside
+    pub fn tls() -> Self {
+        Self::tls_at(Scope::Hypernet)
+    }
+
+    // ABA outside with the matching BAB inside
+    pub fn ssl() -> Self {
+        Self::ssl_at(Scope::Hypernet)
+    }
+
+    // TLS with only `hypernet` forbidden to contain an ABBA
+    pub fn tls_at(hypernet: Scope) -> Self {
+        let abba = Pattern::template("ABBA");
+
+        Self::new()
+            .with_outside(abba.clone())
+            .without_at(hypernet, abba)
+    }
+
+    // SSL with the BAB looked up only in `hypernet`
+    pub fn ssl_at(hypernet: Scope) -> Self {
+        Self::new().with_pair_at(
+            Scope::Supernet,
+            Pattern::template("ABA"),
+            hypernet,
+            Pattern::template("BAB"),
+        )
+    }
+
+    pub fn with_outside(self, pattern: Pattern) -> Self {
+        self.with_at(Scope::Supernet, pattern)
+    }
+
+    pub fn without_inside(self, pattern: Pattern) -> Self {
+        self.without_at(Scope::Hypernet, pattern)
+    }
+
+    pub fn with_pair(self, outside: Pattern, inside: Pattern) -> Self {
+        self.with_pair_at(Scope::Supernet, outside, Scope::Hypernet, inside)
+    }
+
+    pub fn with_at(mut self, scope: Scope, pattern: Pattern) -> Self {
+        self.conditions.push(Condition::Contains(scope, pattern));
+        self
+    }
+
+    pub fn without_at(mut self, scope: Scope, pattern: Pattern) -> Self {
+        self.conditions.push(Condition::Excludes(scope, pattern));
+        self
+    }
+
+    pub fn with_pair_at(
+        mut self,
+        scope: Scope,
+        pattern: Pattern,
+        counterpart_scope: Scope,
+        counterpart: Pattern,
+    ) -> Self {
+        self.conditions.push(Condition::Paired {
+            scope,
+            pattern,
+            counterpart_scope,
+            counterpart,
+        });
+        self
+    }
+
+    pub fn supports(&self, ipv7: &IpV7) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.holds(ipv7))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supports(rule: &Rule, address: &str) -> bool {
+        rule.supports(&IpV7::parse(address).unwrap())
+    }
+
+    #[test]
+    fn test_tls() {
+        let tls = Rule::tls();
+
+        assert!(supports(&tls, "abba[mnop]qrst"));
+        assert!(!supports(&tls, "abcd[bddb]xyyx"));
+        assert!(!supports(&tls, "aaaa[qwer]tyui"));
+    }
+
+    #[test]
+    fn test_ssl() {
+        let ssl = Rule::ssl();
+
+        assert!(supports(&ssl, "aba[bab]xyz"));
+        assert!(supports(&ssl, "zazbz[bzb]cdb"));
+        assert!(!supports(&ssl, "xyx[xyx]xyx"));
+    }
+
+    #[test]
+    fn test_nested() {
+        let nested = |rule: &Rule, address| rule.supports(&IpV7::parse_nested(address).unwrap());
+
+        // ABBA at depth 2 is a hypernet ABBA by default
+        assert!(!nested(&Rule::tls(), "abba[xy[oxxo]z]"));
+        assert!(nested(&Rule::ssl(), "aba[x[bab]]"));
+
+        // Only depth 1 is forbidden to contain an ABBA
+        let abba = "ABBA".parse::<Pattern>().unwrap();
+        let rule = Rule::new()
+            .with_outside(abba.clone())
+            .without_at(Scope::Depth(1), abba);
+
+        assert!(nested(&rule, "abba[xy[oxxo]z]"));
+        assert!(!nested(&rule, "abba[oxxo[xy]z]"));
+        assert_eq!(rule, Rule::tls_at(Scope::Depth(1)));
+
+        // BAB at depth 2 only counts for SSL at depth 2
+        assert!(!nested(&Rule::ssl_at(Scope::Depth(1)), "aba[x[bab]]"));
+        assert!(nested(&Rule::ssl_at(Scope::Depth(2)), "aba[x[bab]]"));
+    }
+
+    #[test]
+    fn test_custom() {
+        // ABCBA outside, no ABA inside
+        let rule = Rule::new()
+            .with_outside("ABCBA".parse().unwrap())
+            .without_inside("ABA".parse().unwrap());
+
+        assert!(supports(&rule, "xyzyx[abcd]"));
+        assert!(!supports(&rule, "xyzyx[aba]"));
+        assert!(!supports(&rule, "xyxyx[abcd]"));
+        assert!(supports(&Rule::new(), "abcd"));
+    }
+}
@@ -0,0 +1,164 @@
+// Single pass TLS/SSL check without allocations. Gives the same answers as the TLS and SSL rules
+// on addresses accepted by IpV7::parse (or IpV7::parse_nested for check_nested), invalid
+// addresses support neither.
+
+const LETTERS: usize = 26;
+const WORDS: usize = (LETTERS * LETTERS).div_ceil(64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Support {
+    pub tls: bool,
+    pub ssl: bool,
+}
+
+// One bit for every (a, b) letter pair of an ABA
+#[derive(Debug, Default)]
+struct PairSet([u64; WORDS]);
+
+impl PairSet {
+    fn index(a: u8, b: u8) -> usize {
+        (a - b'a') as usize * LETTERS + (b - b'a') as usize
+    }
+
+    fn insert(&mut self, a: u8, b: u8) {
+        let index = Self::index(a, b);
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    fn contains(&self, a: u8, b: u8) -> bool {
+        let index = Self::index(a, b);
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+}
+
+pub fn check(address: &str) -> Support {
+    scan(address.as_bytes(), false).unwrap_or_default()
+}
+
+// Nested hypernet sequences are allowed, every depth >= 1 counts as hypernet
+pub fn check_nested(address: &str) -> Support {
+    scan(address.as_bytes(), true).unwrap_or_default()
+}
+
+// None for an invalid address
+fn scan(bytes: &[u8], nested: bool) -> Option<Support> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    let mut depth = 0;
+    // Start of the current sequence, windows never cross a bracket
+    let mut start = 0;
+
+    let mut supernet_abba = false;
+    let mut hypernet_abba = false;
+    // ABAs of supernets and BABs of hypernets, both stored as the (a, b) of the supernet ABA
+    let mut abas = PairSet::default();
+    let mut babs = PairSet::default();
+    let mut ssl = false;
+
+    for (index, byte) in bytes.iter().copied().enumerate() {
+        match byte {
+            b'[' if nested || depth == 0 => {
+                depth += 1;
+                start = index + 1;
+            }
+            b']' if depth > 0 && bytes[index - 1] != b'[' => {
+                depth -= 1;
+                start = index + 1;
+            }
+            b'a'..=b'z' => {
+                let length = index + 1 - start;
+
+                if length >= 3 {
+                    let (a, b) = (bytes[index - 2], bytes[index - 1]);
+
+                    if a == byte && a != b {
+                        if depth == 0 {
+                            abas.insert(a, b);
+                            ssl |= babs.contains(a, b);
+                        } else {
+                            babs.insert(b, a);
+                            ssl |= abas.contains(b, a);
+                        }
+                    }
+                }
+
+                if length >= 4 {
+                    let window = &bytes[index - 3..=index];
+
+                    if window[0] == window[3] && window[1] == window[2] && window[0] != window[1] {
+                        if depth == 0 {
+                            supernet_abba = true;
+                        } else {
+                            hypernet_abba = true;
+                        }
+                    }
+                }
+            }
+            // Nested, unbalanced or empty brackets and other characters
+            _ => return None,
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+
+    Some(Support {
+        tls: supernet_abba && !hypernet_abba,
+        ssl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls() {
+        assert!(check("abba[mnop]qrst").tls);
+        assert!(check("ioxxoj[asdfgh]zxcvbn").tls);
+        assert!(!check("abcd[bddb]xyyx").tls);
+        assert!(!check("aaaa[qwer]tyui").tls);
+    }
+
+    #[test]
+    fn test_ssl() {
+        assert!(check("aba[bab]xyz").ssl);
+        assert!(check("aaa[kek]eke").ssl);
+        assert!(check("zazbz[bzb]cdb").ssl);
+        assert!(!check("xyx[xyx]xyx").ssl);
+        assert!(!check("aaaa[qwer]tyui").ssl);
+    }
+
+    #[test]
+    fn test_windows_do_not_cross_brackets() {
+        // "ab[ba" would be an ABBA and "ab[a" an ABA matching "bab" without the bracket
+        assert!(!check("xab[ba]x").tls);
+        assert!(!check("ab[abab]").ssl);
+    }
+
+    #[test]
+    fn test_nested() {
+        assert_eq!(check("abba[xy[oxxo]z]"), Support::default());
+        assert!(!check_nested("abba[xy[oxxo]z]").tls);
+        assert!(check_nested("abba[xy[zw]z]").tls);
+        assert!(check_nested("aba[x[bab]]").ssl);
+        assert_eq!(check_nested("a[b[]]"), Support::default());
+    }
+
+    #[test]
+    fn test_invalid() {
+        for address in [
+            "",
+            "ab[cd",
+            "ab]cd",
+            "ab[]cd",
+            "ab[c[d]e]f",
+            "abba[mnop]qrsT",
+        ] {
+            assert_eq!(check(address), Support::default(), "{address}");
+        }
+    }
+}
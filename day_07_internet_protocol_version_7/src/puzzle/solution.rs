@@ -5,9 +5,15 @@ use puzzler::env::project;
 use puzzler::puzzler::puzzle::Puzzle;
 
 use crate::puzzle::ipv7::IpV7;
+use crate::puzzle::rule::Rule;
+use crate::puzzle::scanner::{self, Support};
 
 pub struct Solution {
     addresses: Vec<String>,
+    // Allow hypernet sequences inside hypernet sequences
+    nested: bool,
+    // TLS and SSL rules checked instead of the scanner, e.g. with different rules per depth
+    rules: Option<(Rule, Rule)>,
 }
 
 impl Puzzle for Solution {
@@ -23,6 +29,16 @@ impl Puzzle for Solution {
     }
 
     fn parse_content(&mut self, lines: Vec<String>) -> Result<(), Box<dyn Error>> {
+        for (index, line) in lines.iter().enumerate() {
+            let ipv7 = if self.nested {
+                IpV7::parse_nested(line)
+            } else {
+                IpV7::parse(line)
+            };
+
+            ipv7.map_err(|err| format!("Invalid address {} '{line}' [{err}]", index + 1))?;
+        }
+
         self.addresses = lines;
         Ok(())
     }
@@ -31,7 +47,7 @@ impl Puzzle for Solution {
         let tls_count = self
             .addresses
             .iter()
-            .filter(|url| Self::supports_tls(url))
+            .filter(|url| self.check(url).tls)
             .count();
 
         Ok(tls_count.to_string())
@@ -41,7 +57,7 @@ impl Puzzle for Solution {
         let ssl_count = self
             .addresses
             .iter()
-            .filter(|url| Self::supports_ssl(url))
+            .filter(|url| self.check(url).ssl)
             .count();
 
         Ok(ssl_count.to_string())
@@ -52,87 +68,58 @@ impl Solution {
     pub fn new() -> Self {
         Self {
             addresses: Vec::new(),
+            nested: false,
+            rules: None,
         }
     }
 
-    fn supports_tls(address: &str) -> bool {
-        // Traverse string only once and collect all positions
-        let ipv7 = IpV7::new(address);
-
-        // If inside part contains ABBA TLS is not supported
-        for inside in &ipv7.inside {
-            if Self::has_abba(inside) {
-                return false;
-            }
-        }
-
-        // If outside part contains ABBA TLS is supported
-        for outside in &ipv7.outside {
-            if Self::has_abba(outside) {
-                return true;
-            }
-        }
-
-        false
+    pub fn with_nested(mut self, nested: bool) -> Self {
+        self.nested = nested;
+        self
     }
 
-    fn has_abba(word: &str) -> bool {
-        let windows = word.as_bytes().windows(4);
+    // Rules see the depth of every sequence, so nested addresses are accepted with them
+    pub fn with_rules(mut self, tls: Rule, ssl: Rule) -> Self {
+        self.nested = true;
+        self.rules = Some((tls, ssl));
+        self
+    }
 
-        for bytes in windows {
-            if bytes[0] == bytes[3] && bytes[1] == bytes[2] && bytes[0] != bytes[1] {
-                return true;
-            }
+    fn check(&self, address: &str) -> Support {
+        match &self.rules {
+            Some((tls, ssl)) => IpV7::parse_nested(address)
+                .map(|ipv7| Support {
+                    tls: tls.supports(&ipv7),
+                    ssl: ssl.supports(&ipv7),
+                })
+                .unwrap_or_default(),
+            None if self.nested => scanner::check_nested(address),
+            None => scanner::check(address),
         }
-
-        false
     }
 
-    fn supports_ssl(address: &str) -> bool {
-        // Traverse string only once and collect all positions
-        let ipv7 = IpV7::new(address);
-
-        // Inside part shall contain ABA and outide BAB (or vice versa)
-        let abas = ipv7
-            .inside
-            .iter()
-            .flat_map(|url| Self::get_aba(url))
-            .collect::<Vec<_>>();
-
-        let babs = ipv7
-            .outside
-            .iter()
-            .flat_map(|url| Self::get_aba(url))
-            .collect::<Vec<_>>();
-
-        // At least one ABA has to match BAA
-        for aba in &abas {
-            let aba_bytes = aba.as_bytes();
-
-            for bab in &babs {
-                let bab_bytes = bab.as_bytes();
-
-                if aba_bytes[0] == bab_bytes[1] && aba_bytes[1] == bab_bytes[0] {
-                    return true;
-                }
-            }
-        }
+    pub fn get_addresses(&self) -> &[String] {
+        &self.addresses
+    }
 
-        false
+    // Rule based checks, same answers as the scanner used to solve the puzzle. Invalid addresses
+    // support neither TLS nor SSL.
+    pub fn supports_tls(address: &str) -> bool {
+        Self::supports(address, &Rule::tls())
     }
 
-    // Returns only ABA ranges
-    fn get_aba(word: &str) -> Vec<&str> {
-        let mut abas = Vec::new();
-        let windows = word.as_bytes().windows(3);
+    pub fn supports_ssl(address: &str) -> bool {
+        Self::supports(address, &Rule::ssl())
+    }
 
-        for (index, bytes) in windows.enumerate() {
-            if bytes[0] == bytes[2] && bytes[0] != bytes[1] {
-                abas.push(&word[index..index + 3]);
-            }
-        }
+    fn supports(address: &str, rule: &Rule) -> bool {
+        IpV7::parse(address).is_ok_and(|ipv7| rule.supports(&ipv7))
+    }
+}
 
-        abas
+impl Default for Solution {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -140,6 +127,10 @@ impl Solution {
 mod tests {
     use puzzler::puzzler::puzzle::Puzzle;
 
+    use crate::puzzle::explanation::Explanation;
+    use crate::puzzle::ipv7::Scope;
+    use crate::puzzle::rule::Rule;
+    use crate::puzzle::scanner;
     use crate::puzzle::solution::Solution;
 
     fn get_puzzle() -> Solution {
@@ -163,16 +154,82 @@ mod tests {
     }
 
     #[test]
-    fn test_has_abba() {
-        assert!(Solution::has_abba("abba"));
-        assert!(Solution::has_abba("oxyyxo"));
+    fn test_explanation_matches_solution() {
+        for address in get_puzzle().get_addresses() {
+            let explanation = Explanation::new(address).unwrap();
+
+            assert_eq!(explanation.tls.supported(), Solution::supports_tls(address));
+            assert_eq!(explanation.ssl.supported(), Solution::supports_ssl(address));
+        }
+    }
+
+    #[test]
+    fn test_scanner_matches_rules() {
+        for address in get_puzzle().get_addresses() {
+            let support = scanner::check(address);
+
+            assert_eq!(support.tls, Solution::supports_tls(address), "{address}");
+            assert_eq!(support.ssl, Solution::supports_ssl(address), "{address}");
+        }
     }
 
     #[test]
-    fn test_does_not_have_abba() {
-        assert!(!Solution::has_abba("abca"));
-        assert!(!Solution::has_abba("abbc"));
-        assert!(!Solution::has_abba("aaaa"));
+    fn test_nested() {
+        let lines = vec![
+            "abba[xy[oxxo]z]".to_string(),
+            "abba[xy[zw]z]aba[bab]".to_string(),
+        ];
+
+        assert!(Solution::new().parse_content(lines.clone()).is_err());
+
+        let mut solution = Solution::new().with_nested(true);
+        solution.parse_content(lines).unwrap();
+
+        assert_eq!(solution.solve_part1().unwrap(), "1");
+        assert_eq!(solution.solve_part2().unwrap(), "1");
+    }
+
+    #[test]
+    fn test_with_rules() {
+        let lines = vec![
+            "abba[xy[oxxo]z]".to_string(),
+            "abba[oxxo[xy]z]".to_string(),
+            "aba[x[bab]]".to_string(),
+        ];
+
+        // Depth 2 ABBA and BAB are ignored when only depth 1 counts as hypernet
+        let depth = Scope::Depth(1);
+        let mut solution = Solution::new().with_rules(Rule::tls_at(depth), Rule::ssl_at(depth));
+        solution.parse_content(lines.clone()).unwrap();
+
+        assert_eq!(solution.solve_part1().unwrap(), "1");
+        assert_eq!(solution.solve_part2().unwrap(), "0");
+
+        // Default rules give the same answers as the nested scanner
+        let mut solution = Solution::new().with_rules(Rule::tls(), Rule::ssl());
+        solution.parse_content(lines).unwrap();
+
+        assert_eq!(solution.solve_part1().unwrap(), "0");
+        assert_eq!(solution.solve_part2().unwrap(), "1");
+    }
+
+    #[test]
+    fn test_parse_content_invalid() {
+        let mut solution = Solution::new();
+
+        assert_eq!(
+            solution
+                .parse_content(vec!["abba[mnop]qrst".to_string(), "ab[cd".to_string()])
+                .unwrap_err()
+                .to_string(),
+            "Invalid address 2 'ab[cd' [Unclosed '[' at 2]"
+        );
+    }
+
+    #[test]
+    fn test_invalid_address() {
+        assert!(!Solution::supports_tls("abba[mn[op]]qrst"));
+        assert!(!Solution::supports_ssl("aba[bab"));
     }
 
     #[test]
@@ -190,22 +247,6 @@ mod tests {
         ));
     }
 
-    #[test]
-    fn test_has_aba() {
-        assert_eq!(Solution::get_aba("aba"), vec!["aba"]);
-        assert_eq!(Solution::get_aba("abcaba"), vec!["aba"]);
-        assert_eq!(Solution::get_aba("abcabaxy"), vec!["aba"]);
-    }
-
-    #[test]
-    fn test_does_not_have_aba() {
-        let empty: Vec<&str> = Vec::new();
-
-        assert_eq!(Solution::get_aba("abc"), empty);
-        assert_eq!(Solution::get_aba("abb"), empty);
-        assert_eq!(Solution::get_aba("aaa"), empty);
-    }
-
     #[test]
     fn test_supports_ssl() {
         assert!(Solution::supports_ssl("aba[bab]xyz"));